@@ -0,0 +1,193 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike};
+use crate::task::Task;
+
+// Vistas de agenda y calendario de las tareas pendientes. Cada tarea aparece en
+// su recordatorio (la hora programada, o la del snooze si está pospuesta) y en
+// su fecha límite. rustask no tiene tareas recurrentes, así que no hay
+// repeticiones que expandir.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryKind {
+    Scheduled,
+    Snoozed,
+    Due,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub task_id: u64,
+    pub title: String,
+    pub at: DateTime<Local>,
+    pub kind: EntryKind,
+}
+
+impl Entry {
+    pub fn label(&self) -> &'static str {
+        match self.kind {
+            EntryKind::Scheduled => "🕐",
+            EntryKind::Snoozed => "⏸️",
+            EntryKind::Due => "⏰ vence",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Agenda {
+    // Lo de días anteriores a `from` que sigue pendiente
+    pub overdue: Vec<Entry>,
+    // Todos los días entre `from` y `to`, aunque no tengan nada
+    pub days: Vec<(NaiveDate, Vec<Entry>)>,
+}
+
+const MONTHS: [&str; 12] = ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio",
+                            "agosto", "septiembre", "octubre", "noviembre", "diciembre"];
+
+// Entradas de las tareas pendientes, ordenadas por hora
+pub fn entries(tasks: &[Task]) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = tasks.iter()
+        .filter(|t| !t.is_completed())
+        .flat_map(|task| {
+            let reminder = match (task.snoozed_until, task.scheduled_for) {
+                (Some(snoozed), _) => Some((snoozed, EntryKind::Snoozed)),
+                (None, Some(scheduled)) => Some((scheduled, EntryKind::Scheduled)),
+                (None, None) => None,
+            };
+            let due = task.due.map(|due| (due, EntryKind::Due));
+
+            reminder.into_iter().chain(due).map(|(at, kind)| Entry {
+                task_id: task.id,
+                title: task.title.clone(),
+                at,
+                kind,
+            })
+        })
+        .collect();
+    entries.sort_by_key(|e| e.at);
+    entries
+}
+
+pub fn build(tasks: &[Task], from: NaiveDate, to: NaiveDate) -> Agenda {
+    let entries = entries(tasks);
+    let overdue = entries.iter().filter(|e| e.at.date_naive() < from).cloned().collect();
+    let days = from.iter_days()
+        .take_while(|day| *day <= to)
+        .map(|day| (day, entries.iter().filter(|e| e.at.date_naive() == day).cloned().collect()))
+        .collect();
+
+    Agenda { overdue, days }
+}
+
+// Agrupa las entradas de un día por hora (ya vienen ordenadas)
+pub fn by_hour(entries: &[Entry]) -> Vec<(u32, &[Entry])> {
+    entries.chunk_by(|a, b| a.at.hour() == b.at.hour())
+        .map(|group| (group[0].at.hour(), group))
+        .collect()
+}
+
+// Cuadrícula del mes de lunes a domingo. Los días con recordatorios llevan '•',
+// los que tienen una fecha límite '!' y hoy va marcado con '›'
+pub fn month_grid(tasks: &[Task], year: i32, month: u32, today: NaiveDate) -> Option<Vec<String>> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let entries = entries(tasks);
+
+    let mut lines = vec![
+        format!("{} {}", MONTHS[month as usize - 1], year),
+        " lu  ma  mi  ju  vi  sá  do".to_string(),
+    ];
+
+    let mut line = "    ".repeat(first.weekday().num_days_from_monday() as usize);
+    for day in first.iter_days().take_while(|d| d.month() == month) {
+        let on_day: Vec<&Entry> = entries.iter().filter(|e| e.at.date_naive() == day).collect();
+        let marker = if on_day.iter().any(|e| e.kind == EntryKind::Due) {
+            '!'
+        } else if !on_day.is_empty() {
+            '•'
+        } else {
+            ' '
+        };
+        let prefix = if day == today { '›' } else { ' ' };
+        line.push_str(&format!("{}{:>2}{}", prefix, day.day(), marker));
+
+        if day.weekday() == chrono::Weekday::Sun {
+            lines.push(line.trim_end().to_string());
+            line = String::new();
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line.trim_end().to_string());
+    }
+
+    Some(lines)
+}
+
+// Entradas del mes, para la lista debajo del calendario
+pub fn month_entries(tasks: &[Task], year: i32, month: u32) -> Vec<Entry> {
+    entries(tasks).into_iter()
+        .filter(|e| e.at.year() == year && e.at.month() == month)
+        .collect()
+}
+
+// Último día de la semana (domingo) de `day`
+pub fn end_of_week(day: NaiveDate) -> NaiveDate {
+    day + Duration::days(6 - day.weekday().num_days_from_monday() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.from_local_datetime(&NaiveDate::from_ymd_opt(2025, 6, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()).unwrap()
+    }
+
+    fn tasks() -> Vec<Task> {
+        let mut tasks: Vec<Task> = (1..=5).map(|id| Task::new(id, format!("Tarea {}", id), None)).collect();
+        tasks[0].schedule_for(at(9, 10));
+        tasks[1].schedule_for(at(11, 9));
+        tasks[1].due = Some(at(13, 18));
+        // Pospuesta: aparece a la hora del snooze
+        tasks[2].schedule_for(at(11, 8));
+        tasks[2].snooze(at(11, 9));
+        tasks[3].schedule_for(at(12, 16));
+        tasks[3].complete();
+        tasks[4].schedule_for(at(30, 12));
+        tasks
+    }
+
+    #[test]
+    fn test_agenda_groups_by_day_and_hour() {
+        let agenda = build(&tasks(), at(11, 0).date_naive(), end_of_week(at(11, 0).date_naive()));
+        assert_eq!(agenda.overdue.iter().map(|e| e.task_id).collect::<Vec<_>>(), vec![1]);
+
+        // Del miércoles 11 al domingo 15
+        assert_eq!(agenda.days.len(), 5);
+        let (day, entries) = &agenda.days[0];
+        assert_eq!(*day, at(11, 0).date_naive());
+        let hours: Vec<(u32, Vec<(u64, EntryKind)>)> = by_hour(entries).into_iter()
+            .map(|(hour, group)| (hour, group.iter().map(|e| (e.task_id, e.kind)).collect()))
+            .collect();
+        assert_eq!(hours, vec![(9, vec![(2, EntryKind::Scheduled), (3, EntryKind::Snoozed)])]);
+
+        // La completada no aparece
+        assert!(agenda.days[1].1.is_empty());
+        assert_eq!(agenda.days[2].1[0].kind, EntryKind::Due);
+    }
+
+    #[test]
+    fn test_month_grid() {
+        let lines = month_grid(&tasks(), 2025, 6, at(11, 0).date_naive()).unwrap();
+        assert_eq!(lines, vec![
+            "junio 2025",
+            " lu  ma  mi  ju  vi  sá  do",
+            "                          1",
+            "  2   3   4   5   6   7   8",
+            "  9• 10 ›11• 12  13! 14  15",
+            " 16  17  18  19  20  21  22",
+            " 23  24  25  26  27  28  29",
+            " 30•",
+        ]);
+        assert_eq!(month_entries(&tasks(), 2025, 6).len(), 5);
+        assert!(month_grid(&tasks(), 2025, 13, at(11, 0).date_naive()).is_none());
+    }
+}
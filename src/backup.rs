@@ -0,0 +1,127 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use crate::config::Config;
+
+// Copias del archivo de tareas: <dir>/<archivo>.<fecha>.bak
+// Se guarda una antes de cada cambio y se conservan solo las `keep` más recientes.
+pub struct Backups {
+    dir: PathBuf,
+    keep: usize,
+}
+
+#[derive(Debug)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub created_at: DateTime<Local>,
+    pub size: u64,
+}
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+impl Backups {
+    pub fn new(dir: &str, keep: usize) -> Self {
+        Self { dir: PathBuf::from(dir), keep }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.backup_dir, config.backup_count)
+    }
+
+    // Guarda `contents` como copia de `file` y borra las más antiguas
+    pub fn save(&self, file: &str, contents: &[u8]) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+
+        let name = format!("{}.{}.bak", file_name(file), Local::now().format(TIMESTAMP_FORMAT));
+        let path = self.dir.join(name);
+        fs::write(&path, contents)?;
+
+        self.rotate(file)?;
+        Ok(path)
+    }
+
+    // Copia el archivo tal como está ahora
+    pub fn create(&self, file: &str) -> io::Result<PathBuf> {
+        let contents = fs::read(file)?;
+        self.save(file, &contents)
+    }
+
+    // Copias de `file`, de la más reciente a la más antigua
+    pub fn list(&self, file: &str) -> Vec<Backup> {
+        let prefix = format!("{}.", file_name(file));
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut backups: Vec<Backup> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let timestamp = name.strip_prefix(&prefix)?.strip_suffix(".bak")?;
+                let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+
+                Some(Backup {
+                    path: entry.path(),
+                    created_at: Local.from_local_datetime(&created_at).earliest()?,
+                    size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                })
+            })
+            .collect();
+
+        backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+        backups
+    }
+
+    fn rotate(&self, file: &str) -> io::Result<()> {
+        // 0 desactiva las copias automáticas; las hechas con 'backup create' se conservan
+        if self.keep == 0 {
+            return Ok(());
+        }
+        for backup in self.list(file).into_iter().skip(self.keep) {
+            fs::remove_file(backup.path)?;
+        }
+        Ok(())
+    }
+}
+
+fn file_name(file: &str) -> String {
+    Path::new(file)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backups_rotate_and_list_newest_first() {
+        let dir = std::env::temp_dir().join(format!("rustask-backups-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let backups = Backups::new(dir.to_str().unwrap(), 3);
+
+        for i in 0..5 {
+            backups.save("data/tasks.json", format!("version {}", i).as_bytes()).unwrap();
+            // Los nombres llevan milisegundos; esperar para que no coincidan
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        backups.save("todo.txt", b"otra").unwrap();
+
+        let list = backups.list("tasks.json");
+        assert_eq!(list.len(), 3);
+        assert_eq!(fs::read_to_string(&list[0].path).unwrap(), "version 4");
+        assert_eq!(fs::read_to_string(&list[2].path).unwrap(), "version 2");
+        assert_eq!(backups.list("todo.txt").len(), 1);
+
+        // Con 0 no se rota: la copia recién hecha no se borra
+        let manual = Backups::new(dir.to_str().unwrap(), 0);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        manual.save("todo.txt", b"a mano").unwrap();
+        assert_eq!(manual.list("todo.txt").len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use crate::stats::TagStats;
+use crate::task::Task;
+
+// Gráficos de texto para 'chart'. Cada función devuelve las líneas a imprimir.
+
+// Octavos de bloque, de vacío a lleno
+const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+// Niveles del calendario: sin completadas y de menos a más
+const HEAT: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const WEEKDAYS: [&str; 7] = ["L", "M", "X", "J", "V", "S", "D"];
+const MONTHS: [&str; 12] = ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic"];
+
+fn local_day(at: DateTime<chrono::Utc>) -> NaiveDate {
+    at.with_timezone(&Local).date_naive()
+}
+
+// Tareas pendientes al final de cada día entre `from` y `to`. Las tareas
+// eliminadas no se conocen, así que no cuentan.
+pub fn burndown(tasks: &[Task], from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, usize)> {
+    from.iter_days()
+        .take_while(|day| *day <= to)
+        .map(|day| {
+            let pending = tasks.iter()
+                .filter(|t| local_day(t.created_at) <= day)
+                .filter(|t| t.completed_at.is_none_or(|c| local_day(c) > day))
+                .count();
+            (day, pending)
+        })
+        .collect()
+}
+
+// Una columna por punto, de `height` filas, con el eje Y a la izquierda
+pub fn render_burndown(points: &[(NaiveDate, usize)], height: usize) -> Vec<String> {
+    let max = points.iter().map(|(_, count)| *count).max().unwrap_or(0).max(1);
+    let label_width = max.to_string().len();
+    let mut lines = Vec::new();
+
+    for row in (0..height).rev() {
+        let label = if row == height - 1 {
+            max.to_string()
+        } else if row == 0 {
+            "0".to_string()
+        } else {
+            String::new()
+        };
+
+        let columns: String = points.iter()
+            .map(|(_, count)| {
+                let eighths = (*count * height * 8 + max / 2) / max;
+                BLOCKS[eighths.saturating_sub(row * 8).min(8)]
+            })
+            .collect();
+        lines.push(format!("{:>width$} ┤{}", label, columns, width = label_width));
+    }
+
+    lines.push(format!("{:>width$} └{}", "", "─".repeat(points.len()), width = label_width));
+    if let (Some((first, _)), Some((last, _))) = (points.first(), points.last()) {
+        let first = first.format("%d/%m").to_string();
+        let last = last.format("%d/%m").to_string();
+        // La fecha final solo si cabe
+        let dates = match points.len().checked_sub(first.len() + last.len()) {
+            Some(gap) if gap > 0 => format!("{}{}{}", first, " ".repeat(gap), last),
+            _ => first,
+        };
+        lines.push(format!("{:>width$}  {}", "", dates, width = label_width));
+    }
+
+    lines
+}
+
+// Calendario de completadas de las últimas `weeks` semanas (una columna por
+// semana, de lunes a domingo), al estilo de GitHub
+pub fn heatmap(tasks: &[Task], weeks: u32, today: NaiveDate) -> Vec<String> {
+    let start = today - Duration::days(today.weekday().num_days_from_monday() as i64) - Duration::weeks(weeks as i64 - 1);
+    let mut counts = vec![[0usize; 7]; weeks as usize];
+    for day in tasks.iter().filter_map(|t| t.completed_at).map(local_day) {
+        if day >= start && day <= today {
+            let offset = (day - start).num_days() as usize;
+            counts[offset / 7][offset % 7] += 1;
+        }
+    }
+    let max = counts.iter().flatten().copied().max().unwrap_or(0);
+
+    // Nombre del mes sobre la semana en la que empieza, si no se pisa con el anterior
+    let mut header = String::new();
+    for week in 0..weeks as usize {
+        let monday = start + Duration::weeks(week as i64);
+        let starts_month = week == 0 || (0..7).any(|d| (monday + Duration::days(d)).day() == 1);
+        let column = 2 + week * 2;
+        if starts_month && header.chars().count() <= column {
+            header.push_str(&" ".repeat(column - header.chars().count()));
+            header.push_str(MONTHS[(monday + Duration::days(6)).month0() as usize]);
+        }
+    }
+    let mut lines = vec![header.trim_end().to_string()];
+
+    for (weekday, name) in WEEKDAYS.iter().enumerate() {
+        let mut line = format!("{} ", name);
+        for (week, days) in counts.iter().enumerate() {
+            let day = start + Duration::days((week * 7 + weekday) as i64);
+            let cell = if day > today { ' ' } else { heat_level(days[weekday], max) };
+            line.push(cell);
+            line.push(' ');
+        }
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines.push(format!("  Menos {} Más", HEAT.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")));
+    lines
+}
+
+fn heat_level(count: usize, max: usize) -> char {
+    if count == 0 {
+        return HEAT[0];
+    }
+    // 1..=4, proporcional al día con más completadas
+    HEAT[(count * 4).div_ceil(max).clamp(1, 4)]
+}
+
+// Una barra por tag: la parte llena son las completadas y la clara las pendientes
+pub fn tag_bars(tags: &[TagStats], width: usize) -> Vec<String> {
+    let max = tags.iter().map(|t| t.total).max().unwrap_or(0).max(1);
+    let name_width = tags.iter().map(|t| t.tag.chars().count()).max().unwrap_or(0);
+
+    tags.iter()
+        .map(|tag| {
+            let total = (tag.total * width).div_ceil(max);
+            let completed = (tag.completed * width / max).min(total);
+            format!("{:<name_width$} {}{}{} {}/{}",
+                    tag.tag, "█".repeat(completed), "░".repeat(total - completed), " ".repeat(width - total),
+                    tag.completed, tag.total, name_width = name_width)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, d).unwrap()
+    }
+
+    fn task(id: u64, created: u32, completed: Option<u32>) -> Task {
+        let at = |d: u32| Local.from_local_datetime(&day(d).and_hms_opt(12, 0, 0).unwrap()).unwrap().with_timezone(&Utc);
+        let mut task = Task::new(id, format!("Tarea {}", id), None);
+        task.created_at = at(created);
+        if let Some(completed) = completed {
+            task.complete();
+            task.completed_at = Some(at(completed));
+        }
+        task
+    }
+
+    #[test]
+    fn test_burndown() {
+        let tasks = vec![task(1, 1, Some(3)), task(2, 2, None), task(3, 2, Some(4)), task(4, 4, None)];
+        let points = burndown(&tasks, day(1), day(4));
+        assert_eq!(points.iter().map(|(_, count)| *count).collect::<Vec<_>>(), vec![1, 3, 2, 2]);
+
+        let lines = render_burndown(&points, 2);
+        assert_eq!(lines, vec![
+            "3 ┤ █▃▃",
+            "0 ┤▅███",
+            "  └────",
+            "   01/06",
+        ]);
+    }
+
+    #[test]
+    fn test_heatmap_and_tag_bars() {
+        // 11/06/2025 es miércoles
+        let tasks = vec![task(1, 1, Some(9)), task(2, 1, Some(9)), task(3, 1, Some(9)), task(4, 1, Some(11)), task(5, 1, Some(2))];
+        let lines = heatmap(&tasks, 2, day(11));
+        assert_eq!(lines, vec![
+            "  jun",
+            "L ▒ █",
+            "M · ·",
+            "X · ▒",
+            "J ·",
+            "V ·",
+            "S ·",
+            "D ·",
+            "  Menos · ░ ▒ ▓ █ Más",
+        ]);
+
+        let tags = vec![
+            TagStats { tag: "web".to_string(), total: 4, completed: 2, overdue: 0, average_lead_time_hours: None },
+            TagStats { tag: "casa".to_string(), total: 1, completed: 1, overdue: 0, average_lead_time_hours: None },
+        ];
+        assert_eq!(tag_bars(&tags, 8), vec!["web  ████░░░░ 2/4", "casa ██       1/1"]);
+    }
+}
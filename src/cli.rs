@@ -1,5 +1,11 @@
-use clap::{Parser, Subcommand};
-use chrono::NaiveDateTime;
+use clap::{Parser, Subcommand, ValueEnum};
+use crate::config::SmtpSecurity;
+use crate::formats::report::GroupBy;
+use crate::notifier::TaskEvent;
+use crate::storage::StorageFormat;
+use crate::task::Priority;
+use crate::timesheet::TimesheetGroup;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
 
 #[derive(Parser)]
 #[command(name = "rustask")]
@@ -24,6 +30,22 @@ pub enum Commands {
       /// Tags para la tarea (pueden ser múltiples)
       #[arg(short, long)]
       tags: Vec<String>,
+
+      /// Prioridad (high, medium, low)
+      #[arg(short, long, value_enum)]
+      priority: Option<Priority>,
+
+      /// Proyecto al que pertenece la tarea
+      #[arg(long)]
+      project: Option<String>,
+
+      /// Fecha límite (mismos formatos que 'schedule')
+      #[arg(long, value_parser = parse_datetime)]
+      due: Option<DateTime<Local>>,
+
+      /// Tiempo estimado (ej: "30m", "2h", "1h30m")
+      #[arg(long, value_parser = parse_duration)]
+      estimate: Option<Duration>,
   },
   
   /// Listar todas las tareas
@@ -43,29 +65,51 @@ pub enum Commands {
   
   /// Completar una tarea
   Complete {
-      /// ID de la tarea a completar
-      id: u64,
+      /// ID de la tarea a completar (o un prefijo de su UUID)
+      id: String,
   },
   
   /// Eliminar una tarea
   Delete {
-      /// ID de la tarea a eliminar
-      id: u64,
+      /// ID de la tarea a eliminar (o un prefijo de su UUID)
+      id: String,
   },
   
   /// Mostrar estadísticas
-  Stats,
+  Stats {
+      /// Días de la ventana para completadas por día y tendencias (hasta 3650)
+      #[arg(long, default_value_t = 7, value_parser = clap::value_parser!(u32).range(1..=3650))]
+      window: u32,
+
+      /// Semanas a mostrar en completadas por semana (hasta 520)
+      #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..=520))]
+      weeks: u32,
+
+      /// Mostrar las estadísticas como JSON
+      #[arg(long)]
+      json: bool,
+  },
+  
+  /// Gráficos en la terminal: burndown, calendario de completadas y barras por tag
+  Chart {
+      #[command(subcommand)]
+      kind: ChartKind,
+  },
   
   /// Mostrar detalles de una tarea específica
   Show {
-      /// ID de la tarea a mostrar
-      id: u64,
+      /// ID de la tarea a mostrar (o un prefijo de su UUID)
+      id: String,
+
+      /// Mostrar también el historial de cambios de la tarea
+      #[arg(long)]
+      history: bool,
   },
   
   /// Actualizar una tarea existente
   Update {
-      /// ID de la tarea a actualizar
-      id: u64,
+      /// ID de la tarea a actualizar (o un prefijo de su UUID)
+      id: String,
       
       /// Nuevo título (opcional)
       #[arg(short, long)]
@@ -78,12 +122,32 @@ pub enum Commands {
       /// Reemplazar todos los tags con estos nuevos
       #[arg(long)]
       tags: Vec<String>,
+
+      /// Nueva prioridad (high, medium, low)
+      #[arg(short, long, value_enum)]
+      priority: Option<Priority>,
+
+      /// Nuevo proyecto
+      #[arg(long)]
+      project: Option<String>,
+
+      /// Nueva fecha límite (mismos formatos que 'schedule')
+      #[arg(long, value_parser = parse_datetime)]
+      due: Option<DateTime<Local>>,
+
+      /// Nuevo tiempo estimado (ej: "30m", "2h", "1h30m")
+      #[arg(long, value_parser = parse_duration)]
+      estimate: Option<Duration>,
+
+      /// Vaciar campos (ej: --clear due,priority). La descripción y el proyecto también se vacían con ""
+      #[arg(long, value_enum, value_delimiter = ',')]
+      clear: Vec<UpdateField>,
   },
   
   /// Agregar un tag a una tarea
   AddTag {
-      /// ID de la tarea
-      id: u64,
+      /// ID de la tarea (o un prefijo de su UUID)
+      id: String,
       
       /// Tag a agregar
       tag: String,
@@ -91,8 +155,8 @@ pub enum Commands {
   
   /// Remover un tag de una tarea
   RemoveTag {
-      /// ID de la tarea
-      id: u64,
+      /// ID de la tarea (o un prefijo de su UUID)
+      id: String,
       
       /// Tag a remover
       tag: String,
@@ -100,47 +164,743 @@ pub enum Commands {
   
   /// Limpiar todos los tags de una tarea
   ClearTags {
-      /// ID de la tarea
-      id: u64,
+      /// ID de la tarea (o un prefijo de su UUID)
+      id: String,
   },
 
       /// Programar una tarea para una fecha/hora específica
     Schedule {
-        /// ID de la tarea
-        id: u64,
+        /// ID de la tarea (o un prefijo de su UUID)
+        id: String,
         
-        /// Fecha y hora (formato: "DD/MM/YYYY HH:MM" o "DD/MM/YYYY")
+        /// Fecha y hora ("DD/MM/YYYY HH:MM", "DD/MM/YYYY", "mañana 10:00", "vie", "+2h"...)
         #[arg(value_parser = parse_datetime)]
         datetime: chrono::DateTime<chrono::Local>,
     },
 
     /// Posponer un recordatorio
     Snooze {
-        /// ID de la tarea
-        id: u64,
+        /// ID de la tarea (o un prefijo de su UUID)
+        id: String,
         
-        /// Minutos para posponer (default: 10)
+        /// Minutos, un preset ("tonight", "next-week"...) o una fecha como en 'schedule' (default: 10)
         #[arg(default_value = "10")]
-        minutes: i64,
+        when: String,
+    },
+
+    /// Gestionar los presets de snooze
+    SnoozePresets {
+        #[command(subcommand)]
+        action: SnoozePresetsAction,
     },
 
     /// Listar tareas programadas
-    Scheduled
+    Scheduled,
+
+    /// Abrir la interfaz a pantalla completa (lista, detalle y atajos de teclado)
+    Tui,
+
+    /// Revisar una a una las tareas estancadas, sin programar o muy pospuestas
+    Review {
+        /// Revisión semanal: saltar las revisadas en los últimos 7 días (la diaria salta las del último día)
+        #[arg(long)]
+        weekly: bool,
+    },
+
+    /// Agenda de hoy (o de la semana) por día y hora, con las atrasadas primero
+    Agenda {
+        /// Hasta el domingo de esta semana
+        #[arg(long)]
+        week: bool,
+    },
+
+    /// Calendario del mes con los días que tienen tareas programadas o que vencen
+    Calendar {
+        /// Mes a mostrar (MM/YYYY, por defecto el actual)
+        #[arg(value_parser = parse_month)]
+        month: Option<NaiveDate>,
+    },
+
+    /// Activar o desactivar el modo no molestar
+    Dnd {
+        #[command(subcommand)]
+        action: DndAction,
+    },
+
+    /// Configurar horas de silencio para los recordatorios
+    QuietHours {
+        #[command(subcommand)]
+        action: QuietHoursAction,
+    },
+
+    /// Gestionar webhooks para recordatorios y eventos de tareas
+    Webhook {
+        #[command(subcommand)]
+        action: WebhookAction,
+    },
+
+    /// Configurar recordatorios por email (SMTP)
+    Email {
+        #[command(subcommand)]
+        action: EmailAction,
+    },
+
+    /// Exportar las tareas a otro formato
+    Export {
+        /// Formato de salida
+        #[arg(short, long, value_enum)]
+        format: ExportFormat,
+
+        /// Archivo de salida (por defecto se muestra en la terminal)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Agrupar el informe por estado o por tag (solo markdown y org; default: status)
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
+
+        /// Archivo con la plantilla de cada tarea, con marcadores como {{title}} (solo markdown y org)
+        #[arg(long)]
+        template: Option<String>,
+    },
+
+    /// Importar tareas desde otro formato
+    Import {
+        /// Formato del archivo
+        #[arg(short, long, value_enum)]
+        format: ImportFormat,
+
+        /// Archivo a importar
+        file: String,
+
+        /// Mostrar qué se importaría sin guardar nada
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Asociar una columna a un campo de la tarea: "Columna=campo" (pueden ser múltiples)
+        #[arg(long)]
+        map: Vec<String>,
+    },
+
+    /// Ver o cambiar el archivo principal de tareas (JSON, todo.txt o registro de eventos)
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
+    },
+
+    /// Copias de seguridad del archivo de tareas
+    Backup {
+        #[command(subcommand)]
+        action: BackupAction,
+    },
+
+    /// Reescribir el registro de eventos como una sola snapshot (se descarta el historial)
+    Compact,
+
+    /// Empezar a registrar tiempo en una tarea (detiene el cronómetro que estuviera en marcha)
+    Start {
+        /// ID de la tarea (o un prefijo de su UUID)
+        id: String,
+    },
+
+    /// Detener el cronómetro en marcha
+    Stop,
+
+    /// Ciclos de foco y descanso sobre una tarea (sin ID muestra el pomodoro en curso)
+    Pomodoro {
+        /// ID de la tarea (o un prefijo de su UUID)
+        id: Option<String>,
+
+        /// Detener el pomodoro en curso
+        #[arg(long, conflicts_with = "id")]
+        stop: bool,
+    },
+
+    /// Hoja de horas con el tiempo registrado
+    Timesheet {
+        /// Desde este día, inclusive (DD/MM/YYYY, "hoy" o "ayer")
+        #[arg(long, value_parser = parse_date)]
+        from: Option<NaiveDate>,
+
+        /// Hasta este día, inclusive
+        #[arg(long, value_parser = parse_date)]
+        to: Option<NaiveDate>,
+
+        /// Agrupar por día, tag o tarea
+        #[arg(long, value_enum, default_value = "day")]
+        by: TimesheetGroup,
+
+        /// Guardar la hoja de horas como CSV en este archivo
+        #[arg(long)]
+        csv: Option<String>,
+    },
+
+    /// Comparar lo estimado con el tiempo registrado, por tag y en total
+    Estimates,
+
+    /// Trabajo estimado en las tareas programadas de los próximos días frente a las horas disponibles
+    Capacity {
+        /// Cuántos días mirar, empezando hoy
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+    },
+
+    /// Historial de cambios de todas las tareas
+    Log {
+        /// Desde este día, inclusive (DD/MM/YYYY, "hoy" o "ayer")
+        #[arg(long, value_parser = parse_date)]
+        from: Option<NaiveDate>,
+
+        /// Hasta este día, inclusive
+        #[arg(long, value_parser = parse_date)]
+        to: Option<NaiveDate>,
+    },
+}
+
+// Campos opcionales que 'update --clear' puede vaciar
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum UpdateField {
+    Description,
+    Tags,
+    Priority,
+    Project,
+    Due,
+    Estimate,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    #[value(name = "todotxt")]
+    TodoTxt,
+    Ical,
+    Taskwarrior,
+    Markdown,
+    Org,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportFormat {
+    Csv,
+    #[value(name = "todotxt")]
+    TodoTxt,
+    Ical,
+    Taskwarrior,
+}
+
+#[derive(Subcommand)]
+pub enum DndAction {
+    /// Silenciar recordatorios hasta ejecutar 'dnd off'
+    On,
+
+    /// Volver a recibir recordatorios
+    Off,
+
+    /// Silenciar recordatorios hasta una fecha/hora
+    Until {
+        /// Fecha y hora (mismos formatos que 'schedule')
+        #[arg(value_parser = parse_datetime)]
+        datetime: chrono::DateTime<chrono::Local>,
+    },
+
+    /// Mostrar el estado actual
+    Status,
+}
+
+#[derive(Subcommand)]
+pub enum QuietHoursAction {
+    /// Listar las ventanas de silencio configuradas
+    List,
+
+    /// Agregar una ventana de silencio
+    Add {
+        /// Días en que empieza la ventana (ej: "lun-vie", "sab,dom", "todos")
+        #[arg(value_parser = parse_weekdays)]
+        days: ::std::vec::Vec<Weekday>,
+
+        /// Hora de inicio (HH:MM)
+        #[arg(value_parser = parse_time)]
+        start: NaiveTime,
+
+        /// Hora de fin (HH:MM); si es menor que el inicio termina al día siguiente
+        #[arg(value_parser = parse_time)]
+        end: NaiveTime,
+    },
+
+    /// Eliminar una ventana por su número en 'quiet-hours list'
+    Remove {
+        /// Número de la ventana
+        index: usize,
+    },
+
+    /// Eliminar todas las ventanas de silencio
+    Clear,
+}
+
+#[derive(Subcommand)]
+pub enum WebhookAction {
+    /// Listar los webhooks configurados
+    List,
+
+    /// Agregar un webhook
+    Add {
+        /// URL que recibirá un POST con JSON
+        url: String,
+
+        /// Eventos a enviar (por defecto reminder, overdue, added y completed)
+        #[arg(short, long, value_enum)]
+        event: Vec<TaskEvent>,
+
+        /// Plantilla JSON con marcadores: {{event}}, {{id}}, {{title}}, {{description}}, {{tags}}, {{status}}, {{scheduled_for}}, {{task}}
+        #[arg(long)]
+        template: Option<String>,
+    },
+
+    /// Eliminar un webhook por su número en 'webhook list'
+    Remove {
+        /// Número del webhook
+        index: usize,
+    },
+
+    /// Mostrar los envíos pendientes de reintento
+    Outbox,
+
+    /// Reintentar ahora los envíos pendientes
+    Retry,
+}
+
+#[derive(Subcommand)]
+pub enum EmailAction {
+    /// Mostrar la configuración de email
+    Show,
+
+    /// Configurar el servidor SMTP y los destinatarios
+    Set {
+        /// Servidor SMTP (ej: smtp.gmail.com)
+        #[arg(long)]
+        server: String,
+
+        /// Puerto (por defecto 587 con starttls, 465 con tls y 25 sin cifrado)
+        #[arg(long)]
+        port: Option<u16>,
+
+        /// Cifrado de la conexión
+        #[arg(long, value_enum, default_value = "start-tls")]
+        security: SmtpSecurity,
+
+        /// Usuario SMTP
+        #[arg(long)]
+        username: Option<String>,
+
+        /// Contraseña SMTP (también se puede usar RUSTASK_SMTP_PASSWORD)
+        #[arg(long)]
+        password: Option<String>,
+
+        /// Remitente (ej: "RusTask <rustask@example.com>")
+        #[arg(long)]
+        from: String,
+
+        /// Destinatarios (pueden ser múltiples)
+        #[arg(long, required = true)]
+        to: Vec<String>,
+
+        /// Eventos a enviar (por defecto reminder y overdue)
+        #[arg(short, long, value_enum)]
+        event: Vec<TaskEvent>,
+
+        /// Hora de envío de la agenda diaria (HH:MM)
+        #[arg(long, value_parser = parse_time)]
+        agenda: Option<NaiveTime>,
+    },
+
+    /// Desactivar los emails
+    Disable,
+
+    /// Enviar un email de prueba
+    Test,
+
+    /// Enviar la agenda de hoy ahora
+    Agenda,
 }
 
-fn parse_datetime(s: &str) -> Result<chrono::DateTime<chrono::Local>, String> {
-    use chrono::{Local, TimeZone};
+#[derive(Subcommand)]
+pub enum SnoozePresetsAction {
+    /// Listar los presets de snooze
+    List,
+
+    /// Agregar (o reemplazar) un preset
+    Add {
+        /// Nombre del preset (ej: "lunch")
+        name: String,
+
+        /// Cuándo avisar, con los formatos de 'schedule' (ej: "hoy 14:00", "+3h")
+        when: String,
+    },
+
+    /// Eliminar un preset
+    Remove {
+        /// Nombre del preset
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ChartKind {
+    /// Tareas pendientes al final de cada día
+    Burndown {
+        /// Cuántos días mostrar, terminando hoy (hasta 3650)
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..=3650))]
+        days: u32,
+    },
+
+    /// Calendario de completadas por día, al estilo de GitHub
+    Heatmap {
+        /// Cuántas semanas mostrar, terminando en la actual (hasta 520)
+        #[arg(long, default_value_t = 26, value_parser = clap::value_parser!(u32).range(1..=520))]
+        weeks: u32,
+    },
+
+    /// Completadas y pendientes de cada tag
+    Tags,
+}
+
+#[derive(Subcommand)]
+pub enum BackupAction {
+    /// Listar las copias, de la más reciente a la más antigua
+    List,
+
+    /// Crear una copia ahora
+    Create,
+
+    /// Reemplazar las tareas por las de una copia (el estado actual queda en otra copia)
+    Restore {
+        /// Número de la copia en 'backup list' (1 = la más reciente)
+        number: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StorageAction {
+    /// Mostrar el formato y el archivo actuales
+    Show,
+
+    /// Cambiar de formato. Si el archivo ya existe se usan sus tareas; si no, se copian las actuales
+    Use {
+        /// Formato del archivo principal
+        #[arg(value_enum)]
+        format: StorageFormat,
+
+        /// Ruta del todo.txt o del registro de eventos (por defecto la configurada)
+        #[arg(long)]
+        file: Option<String>,
+    },
+}
+
+pub fn parse_datetime(s: &str) -> Result<DateTime<Local>, String> {
+    parse_datetime_from(s, Local::now())
+}
+
+// Formatos aceptados:
+//   "DD/MM/YYYY HH:MM", "DD/MM/YYYY" (09:00 por defecto)
+//   "+2h", "en 30m", "in 1d"           relativo a ahora
+//   "hoy 18:00", "mañana", "vie 10:00" día relativo (09:00 por defecto)
+//   "18:00"                            hoy, o mañana si esa hora ya pasó
+pub fn parse_datetime_from(s: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+    let s = s.trim().to_lowercase();
+    let default_time = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
     
     // Intentar formato con hora
-    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%d/%m/%Y %H:%M") {
-        return Ok(Local.from_local_datetime(&dt).unwrap());
+    if let Ok(dt) = NaiveDateTime::parse_from_str(&s, "%d/%m/%Y %H:%M") {
+        return to_local(dt);
     }
     
     // Intentar formato solo fecha (usar 09:00 por defecto)
-    if let Ok(date) = chrono::NaiveDate::parse_from_str(s, "%d/%m/%Y") {
-        let dt = date.and_hms_opt(9, 0, 0).unwrap();
-        return Ok(Local.from_local_datetime(&dt).unwrap());
+    if let Ok(date) = NaiveDate::parse_from_str(&s, "%d/%m/%Y") {
+        return to_local(date.and_time(default_time));
+    }
+
+    // Relativo a ahora
+    let relative = s.strip_prefix('+')
+        .or_else(|| s.strip_prefix("en "))
+        .or_else(|| s.strip_prefix("in "));
+    if let Some(rest) = relative {
+        return parse_duration(rest.trim()).and_then(|d| add_duration(now, d));
+    }
+
+    // Día relativo con hora opcional
+    let (day, time) = match s.split_once(' ') {
+        Some((day, time)) => (day, Some(parse_time(time.trim())?)),
+        None => (s.as_str(), None),
+    };
+    let today = now.date_naive();
+    let date = match day {
+        "hoy" | "today" => Some(today),
+        "mañana" | "manana" | "tomorrow" => Some(today + Duration::days(1)),
+        other => parse_weekday(other).ok().map(|weekday| {
+            // Siempre la próxima ocurrencia (nunca hoy)
+            let days_ahead = (weekday.num_days_from_monday() + 7
+                - today.weekday().num_days_from_monday()) % 7;
+            today + Duration::days(if days_ahead == 0 { 7 } else { days_ahead as i64 })
+        }),
+    };
+    if let Some(date) = date {
+        return to_local(date.and_time(time.unwrap_or(default_time)));
+    }
+
+    // Solo hora
+    if let Ok(time) = parse_time(&s) {
+        let dt = to_local(today.and_time(time))?;
+        return if dt > now { Ok(dt) } else { to_local((today + Duration::days(1)).and_time(time)) };
     }
     
-    Err(format!("Formato de fecha inválido. Use: DD/MM/YYYY HH:MM o DD/MM/YYYY"))
-}
\ No newline at end of file
+    Err("Formato de fecha inválido. Use: DD/MM/YYYY HH:MM, DD/MM/YYYY, \"mañana 10:00\", \"vie\", \"18:00\" o \"+2h\"".to_string())
+}
+
+// Un día: "DD/MM/YYYY", "hoy" o "ayer"
+pub fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    let today = Local::now().date_naive();
+    match s.trim().to_lowercase().as_str() {
+        "hoy" | "today" => Ok(today),
+        "ayer" | "yesterday" => Ok(today - Duration::days(1)),
+        other => NaiveDate::parse_from_str(other, "%d/%m/%Y")
+            .map_err(|_| "Formato de fecha inválido. Use: DD/MM/YYYY, \"hoy\" o \"ayer\"".to_string()),
+    }
+}
+
+// Un mes: "MM/YYYY" (devuelve su primer día)
+pub fn parse_month(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("01/{}", s.trim()), "%d/%m/%Y")
+        .map_err(|_| "Formato de mes inválido. Use: MM/YYYY".to_string())
+}
+
+fn to_local(dt: NaiveDateTime) -> Result<DateTime<Local>, String> {
+    Local.from_local_datetime(&dt)
+        .earliest()
+        .ok_or_else(|| format!("La hora {} no existe en la zona horaria local", dt.format("%d/%m/%Y %H:%M")))
+}
+
+// Duraciones como "30m", "2h", "1h30m", "3d" o "1w" (un número solo son minutos)
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("Duración inválida: '{}'. Use: 30m, 2h, 1h30m, 3d, 1w", s);
+
+    let too_long = || format!("Duración demasiado larga: '{}'", s);
+
+    if let Ok(minutes) = s.parse::<i64>() {
+        return Duration::try_minutes(minutes).ok_or_else(too_long);
+    }
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let value: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        let part = match c {
+            'm' => Duration::try_minutes(value),
+            'h' => Duration::try_hours(value),
+            'd' => Duration::try_days(value),
+            'w' => Duration::try_weeks(value),
+            _ => return Err(invalid()),
+        };
+        total = part.and_then(|part| total.checked_add(&part)).ok_or_else(too_long)?;
+    }
+
+    if !number.is_empty() || s.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(total)
+}
+
+// `now + d` sin pasarse del rango de fechas (ej: "+99999999999999w")
+pub fn add_duration(now: DateTime<Local>, d: Duration) -> Result<DateTime<Local>, String> {
+    now.checked_add_signed(d).ok_or_else(|| "La fecha queda fuera de rango".to_string())
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .map_err(|_| "Formato de hora inválido. Use: HH:MM".to_string())
+}
+
+// Acepta días sueltos, listas y rangos: "lun", "sab,dom", "lun-vie", "todos"
+fn parse_weekdays(s: &str) -> Result<Vec<Weekday>, String> {
+    let s = s.to_lowercase();
+    if s == "todos" || s == "all" {
+        return Ok(weekday_range(Weekday::Mon, Weekday::Sun));
+    }
+
+    let mut days = Vec::new();
+    for part in s.split(',') {
+        let range: Vec<Weekday> = match part.split_once('-') {
+            Some((from, to)) => weekday_range(parse_weekday(from)?, parse_weekday(to)?),
+            None => vec![parse_weekday(part)?],
+        };
+
+        for day in range {
+            if !days.contains(&day) {
+                days.push(day);
+            }
+        }
+    }
+
+    Ok(days)
+}
+
+fn weekday_range(from: Weekday, to: Weekday) -> Vec<Weekday> {
+    let mut days = vec![from];
+    let mut day = from;
+    while day != to {
+        day = day.succ();
+        days.push(day);
+    }
+    days
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday, String> {
+    match s.trim() {
+        "lun" | "lunes" | "mon" => Ok(Weekday::Mon),
+        "mar" | "martes" | "tue" => Ok(Weekday::Tue),
+        "mie" | "mié" | "miercoles" | "miércoles" | "wed" => Ok(Weekday::Wed),
+        "jue" | "jueves" | "thu" => Ok(Weekday::Thu),
+        "vie" | "viernes" | "fri" => Ok(Weekday::Fri),
+        "sab" | "sáb" | "sabado" | "sábado" | "sat" => Ok(Weekday::Sat),
+        "dom" | "domingo" | "sun" => Ok(Weekday::Sun),
+        other => Err(format!("Día inválido: '{}'. Use: lun, mar, mie, jue, vie, sab, dom", other)),
+    }
+}
+
+// Separa una línea del modo interactivo en argumentos, como un shell POSIX:
+// espacios y tabs separan; entre comillas simples todo es literal; entre
+// comillas dobles '\' escapa '"', '\', '$' y '`'; fuera de comillas escapa
+// cualquier carácter. Los trozos pegados forman un solo argumento y '""' es un
+// argumento vacío.
+pub fn parse_args(input: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // Distingue un argumento vacío entre comillas de no tener argumento
+    let mut started = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => {
+                if started {
+                    args.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            },
+            '\'' => {
+                started = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Comilla simple (') sin cerrar".to_string()),
+                    }
+                }
+            },
+            '"' => {
+                started = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            },
+                            None => return Err("Comilla doble (\") sin cerrar".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Comilla doble (\") sin cerrar".to_string()),
+                    }
+                }
+            },
+            '\\' => match chars.next() {
+                Some(c) => {
+                    current.push(c);
+                    started = true;
+                },
+                None => return Err("Barra invertida (\\) al final de la línea: no hay nada que escapar".to_string()),
+            },
+            c => {
+                current.push(c);
+                started = true;
+            },
+        }
+    }
+
+    if started {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025-06-04 es miércoles
+    fn now() -> DateTime<Local> {
+        let dt = NaiveDate::from_ymd_opt(2025, 6, 4).unwrap().and_hms_opt(15, 0, 0).unwrap();
+        Local.from_local_datetime(&dt).unwrap()
+    }
+
+    fn local(d: u32, h: u32, m: u32) -> DateTime<Local> {
+        let dt = NaiveDate::from_ymd_opt(2025, 6, d).unwrap().and_hms_opt(h, m, 0).unwrap();
+        Local.from_local_datetime(&dt).unwrap()
+    }
+
+    #[test]
+    fn test_parse_absolute_datetime() {
+        assert_eq!(parse_datetime_from("06/06/2025 14:30", now()), Ok(local(6, 14, 30)));
+        assert_eq!(parse_datetime_from("06/06/2025", now()), Ok(local(6, 9, 0)));
+    }
+
+    #[test]
+    fn test_parse_relative_datetime() {
+        assert_eq!(parse_datetime_from("+2h", now()), Ok(local(4, 17, 0)));
+        assert_eq!(parse_datetime_from("en 1h30m", now()), Ok(local(4, 16, 30)));
+        assert_eq!(parse_datetime_from("mañana", now()), Ok(local(5, 9, 0)));
+        assert_eq!(parse_datetime_from("hoy 20:00", now()), Ok(local(4, 20, 0)));
+        assert_eq!(parse_datetime_from("vie 10:00", now()), Ok(local(6, 10, 0)));
+        // El mismo día de la semana salta a la semana siguiente
+        assert_eq!(parse_datetime_from("mie", now()), Ok(local(11, 9, 0)));
+        // Una hora que ya pasó se interpreta como mañana
+        assert_eq!(parse_datetime_from("08:00", now()), Ok(local(5, 8, 0)));
+        assert!(parse_datetime_from("pronto", now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("45"), Ok(Duration::minutes(45)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("2d"), Ok(Duration::days(2)));
+        assert!(parse_duration("2x").is_err());
+        assert!(parse_duration("h").is_err());
+        // Valores enormes son un error, no un pánico de chrono
+        assert!(parse_duration("99999999999999w").is_err());
+        assert!(parse_duration("99999999999999999").is_err());
+        assert!(parse_datetime_from("+9999999999h", now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |s: &str| parse_args(s).unwrap();
+        assert_eq!(args("add  \"Comprar pan\"\t-t casa"), vec!["add", "Comprar pan", "-t", "casa"]);
+        assert_eq!(args(r#"add "He said \"hi\"" 'a\b "c"'"#), vec!["add", "He said \"hi\"", "a\\b \"c\""]);
+        assert_eq!(args(r"add It\'s\ mine"), vec!["add", "It's mine"]);
+        // Trozos pegados y argumentos vacíos
+        assert_eq!(args(r#"update 3 --project=web"-"'app' -d """#), vec!["update", "3", "--project=web-app", "-d", ""]);
+        assert_eq!(args("  "), Vec::<String>::new());
+
+        assert!(parse_args(r#"add "Comprar pan"#).unwrap_err().contains("doble"));
+        assert!(parse_args("add 'Comprar pan").unwrap_err().contains("simple"));
+        assert!(parse_args(r"add pan\").is_err());
+    }
+}
@@ -0,0 +1,321 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+use clap::ValueEnum;
+use crate::cli;
+use crate::notifier::TaskEvent;
+use crate::storage::StorageFormat;
+
+// Ventana de silencio: empieza en alguno de los días indicados a la hora `start`
+// y termina a la hora `end` (si end <= start, termina al día siguiente)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuietWindow {
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietWindow {
+    // Si `at` cae dentro de la ventana, retorna el momento en que termina
+    fn end_if_inside(&self, at: DateTime<Local>) -> Option<DateTime<Local>> {
+        // Una ventana que empezó ayer puede seguir activa hoy (ej: 22:00 - 07:00)
+        for offset in [1, 0] {
+            let day = at.date_naive() - Duration::days(offset);
+            if !self.days.contains(&day.weekday()) {
+                continue;
+            }
+
+            let end_day = if self.end > self.start { day } else { day + Duration::days(1) };
+            let start = Local.from_local_datetime(&day.and_time(self.start)).earliest();
+            let end = Local.from_local_datetime(&end_day.and_time(self.end)).earliest();
+
+            if let (Some(start), Some(end)) = (start, end) {
+                if start <= at && at < end {
+                    return Some(end);
+                }
+            }
+        }
+        None
+    }
+}
+
+// Preset de snooze con nombre; `when` usa la misma sintaxis que 'schedule'
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnoozePreset {
+    pub name: String,
+    pub when: String,
+}
+
+impl SnoozePreset {
+    fn new(name: &str, when: &str) -> Self {
+        Self { name: name.to_string(), when: when.to_string() }
+    }
+}
+
+// Webhook que recibe los eventos indicados; sin plantilla se envía la tarea en JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default = "WebhookConfig::default_events")]
+    pub events: Vec<TaskEvent>,
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+impl WebhookConfig {
+    // Los eventos del pomodoro solo se envían si se piden con --event
+    pub fn default_events() -> Vec<TaskEvent> {
+        vec![TaskEvent::Reminder, TaskEvent::Overdue, TaskEvent::Added, TaskEvent::Completed]
+    }
+}
+
+// Cómo se cifra la conexión con el servidor SMTP
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    // TLS implícito (normalmente puerto 465)
+    Tls,
+    // STARTTLS (normalmente puerto 587)
+    StartTls,
+    // Sin cifrado, solo para servidores locales
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub server: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default = "EmailConfig::default_events")]
+    pub events: Vec<TaskEvent>,
+    // Hora a la que se envía la agenda diaria (si está configurada)
+    #[serde(default)]
+    pub agenda_time: Option<NaiveTime>,
+}
+
+impl EmailConfig {
+    pub fn default_events() -> Vec<TaskEvent> {
+        vec![TaskEvent::Reminder, TaskEvent::Overdue]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub quiet_hours: Vec<QuietWindow>,
+    // No molestar indefinido (hasta que se desactive con 'dnd off')
+    pub dnd: bool,
+    // No molestar hasta una fecha/hora concreta
+    pub dnd_until: Option<DateTime<Local>>,
+    pub snooze_presets: Vec<SnoozePreset>,
+    // A partir de cuántos snoozes un recordatorio se vuelve insistente
+    pub snooze_limit: u32,
+    // Minutos después de la hora programada para avisar que una tarea está atrasada
+    pub overdue_after_minutes: i64,
+    pub webhooks: Vec<WebhookConfig>,
+    pub email: Option<EmailConfig>,
+    // Último día en que se envió la agenda por email
+    pub last_agenda_sent: Option<NaiveDate>,
+    // Directorio con los ejecutables de hooks (on-add, post-complete...)
+    pub hooks_dir: String,
+    pub hook_timeout_secs: u64,
+    // Formato del archivo principal de tareas y rutas del todo.txt y del registro de eventos
+    pub storage_format: StorageFormat,
+    pub todo_txt_file: String,
+    pub event_log_file: String,
+    // Copias de seguridad del archivo de tareas (0 las desactiva)
+    pub backup_dir: String,
+    pub backup_count: usize,
+    // Duración de los pomodoros y descansos, y cada cuántos pomodoros toca el descanso largo
+    pub pomodoro_minutes: i64,
+    pub short_break_minutes: i64,
+    pub long_break_minutes: i64,
+    pub long_break_every: u32,
+    // Horas de trabajo disponibles por día para el control de capacidad (0 lo desactiva)
+    pub available_hours_per_day: f64,
+    // Días desde que se creó una tarea pendiente (o desde su última revisión) tras
+    // los que 'review' la considera estancada; editarla no reinicia la cuenta
+    pub review_stale_days: i64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            quiet_hours: Vec::new(),
+            dnd: false,
+            dnd_until: None,
+            snooze_presets: vec![
+                SnoozePreset::new("later-today", "+3h"),
+                SnoozePreset::new("tonight", "hoy 20:00"),
+                SnoozePreset::new("tomorrow-morning", "mañana 09:00"),
+                SnoozePreset::new("next-week", "lun 09:00"),
+            ],
+            snooze_limit: 3,
+            overdue_after_minutes: 60,
+            webhooks: Vec::new(),
+            email: None,
+            last_agenda_sent: None,
+            hooks_dir: "hooks".to_string(),
+            hook_timeout_secs: 5,
+            storage_format: StorageFormat::Json,
+            todo_txt_file: "todo.txt".to_string(),
+            event_log_file: "tasks.log".to_string(),
+            backup_dir: "backups".to_string(),
+            backup_count: 10,
+            pomodoro_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 4,
+            available_hours_per_day: 8.0,
+            review_stale_days: 14,
+        }
+    }
+}
+
+impl Config {
+    pub fn load_from_file(path: &str) -> Result<Self, io::Error> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+
+        let data = fs::read_to_string(path)?;
+        serde_json::from_str(&data).map_err(io::Error::other)
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), io::Error> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    pub fn find_snooze_preset(&self, name: &str) -> Option<&SnoozePreset> {
+        self.snooze_presets.iter().find(|p| p.name == name)
+    }
+
+    // Hasta cuándo posponer: minutos, un preset o cualquier fecha de 'schedule'.
+    // Un preset ya pasado hoy (ej: "tonight" a las 21:00) pasa al día siguiente,
+    // y una fecha explícita en el pasado es un error.
+    pub fn resolve_snooze(&self, when: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+        // Un número solo son minutos
+        if when.parse::<i64>().is_ok() {
+            return cli::parse_duration(when).and_then(|d| cli::add_duration(now, d));
+        }
+
+        let until = match self.find_snooze_preset(when) {
+            Some(preset) => {
+                let until = cli::parse_datetime_from(&preset.when, now)
+                    .map_err(|e| format!("Preset '{}' inválido: {}", preset.name, e))?;
+                if until > now { until } else { cli::add_duration(until, Duration::days(1))? }
+            },
+            None => cli::parse_datetime_from(when, now)?,
+        };
+
+        if until <= now {
+            return Err(format!("{} ya pasó", until.format("%d/%m/%Y %H:%M")));
+        }
+        Ok(until)
+    }
+
+    // ¿El recordatorio ya se pospuso demasiadas veces?
+    pub fn is_snooze_escalated(&self, snooze_count: u32) -> bool {
+        snooze_count > self.snooze_limit
+    }
+
+    // Si `at` cae en horas de silencio (o en un 'dnd until'), retorna cuándo terminan.
+    // Ventanas encadenadas se recorren hasta encontrar un momento libre.
+    pub fn quiet_until(&self, at: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut current = at;
+
+        // Límite de iteraciones para no quedar en un bucle con ventanas de 24h
+        for _ in 0..14 {
+            let mut next = None;
+
+            if let Some(until) = self.dnd_until {
+                if current < until {
+                    next = Some(until);
+                }
+            }
+
+            for window in &self.quiet_hours {
+                if let Some(end) = window.end_if_inside(current) {
+                    next = Some(next.map_or(end, |n: DateTime<Local>| n.max(end)));
+                }
+            }
+
+            match next {
+                Some(end) => current = end,
+                None => break,
+            }
+        }
+
+        if current > at {
+            Some(current)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+        let date = chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        Local.from_local_datetime(&date.and_hms_opt(h, min, 0).unwrap()).unwrap()
+    }
+
+    fn night_window(days: Vec<Weekday>) -> QuietWindow {
+        QuietWindow {
+            days,
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_snooze() {
+        let config = Config::default();
+        let now = at(2025, 6, 4, 21, 0);
+
+        assert_eq!(config.resolve_snooze("30", now), Ok(at(2025, 6, 4, 21, 30)));
+        // "tonight" ya pasó: mañana a la noche, no un recordatorio inmediato
+        assert_eq!(config.resolve_snooze("tonight", now), Ok(at(2025, 6, 5, 20, 0)));
+        assert_eq!(config.resolve_snooze("tonight", at(2025, 6, 4, 19, 0)), Ok(at(2025, 6, 4, 20, 0)));
+        assert!(config.resolve_snooze("hoy 20:00", now).unwrap_err().contains("ya pasó"));
+    }
+
+    #[test]
+    fn test_quiet_window_crossing_midnight() {
+        // 2025-06-02 es lunes
+        let config = Config {
+            quiet_hours: vec![night_window(vec![Weekday::Mon])],
+            ..Config::default()
+        };
+
+        assert_eq!(config.quiet_until(at(2025, 6, 2, 23, 30)), Some(at(2025, 6, 3, 7, 0)));
+        assert_eq!(config.quiet_until(at(2025, 6, 3, 6, 59)), Some(at(2025, 6, 3, 7, 0)));
+        assert_eq!(config.quiet_until(at(2025, 6, 3, 7, 0)), None);
+        // El martes a la noche no hay ventana
+        assert_eq!(config.quiet_until(at(2025, 6, 3, 23, 0)), None);
+    }
+
+    #[test]
+    fn test_dnd_until_chains_with_quiet_hours() {
+        let config = Config {
+            quiet_hours: vec![night_window(vec![Weekday::Mon])],
+            dnd_until: Some(at(2025, 6, 2, 22, 30)),
+            ..Config::default()
+        };
+
+        // El DND termina dentro de la ventana nocturna, que continúa hasta las 7:00
+        assert_eq!(config.quiet_until(at(2025, 6, 2, 20, 0)), Some(at(2025, 6, 3, 7, 0)));
+    }
+}
@@ -0,0 +1,289 @@
+use std::thread;
+use std::time::Duration;
+use chrono::{DateTime, Local, NaiveDate};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use crate::config::{Config, EmailConfig, SmtpSecurity};
+use crate::notifier::{Notification, Notifier, TaskEvent};
+use crate::task::Task;
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(15);
+// Si no hay contraseña en config.json se busca en esta variable de entorno
+const PASSWORD_ENV: &str = "RUSTASK_SMTP_PASSWORD";
+
+// Envía recordatorios por email a través de un servidor SMTP
+pub struct EmailNotifier;
+
+impl Notifier for EmailNotifier {
+    fn notify(&self, config: &Config, notification: &Notification) {
+        let email = match config.email {
+            Some(ref email) if email.events.contains(&notification.event) => email.clone(),
+            _ => return,
+        };
+
+        let (subject, plain, html) = render_task_email(notification);
+
+        // Enviar en segundo plano para no bloquear la terminal ni el scheduler
+        thread::spawn(move || {
+            if let Err(e) = send_email(&email, &subject, plain, html) {
+                eprintln!("⚠️ Error al enviar email: {}", e);
+            }
+        });
+    }
+}
+
+pub fn send_email(email: &EmailConfig, subject: &str, plain: String, html: String) -> Result<(), String> {
+    let from: Mailbox = email.from.parse().map_err(|e| format!("Remitente inválido '{}': {}", email.from, e))?;
+
+    let mut builder = Message::builder().from(from).subject(subject);
+    for to in &email.to {
+        let to: Mailbox = to.parse().map_err(|e| format!("Destinatario inválido '{}': {}", to, e))?;
+        builder = builder.to(to);
+    }
+
+    let message = builder
+        .multipart(MultiPart::alternative_plain_html(plain, html))
+        .map_err(|e| e.to_string())?;
+
+    let transport = build_transport(email)?;
+    transport.send(&message).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn build_transport(email: &EmailConfig) -> Result<SmtpTransport, String> {
+    let builder = match email.security {
+        SmtpSecurity::Tls => SmtpTransport::relay(&email.server).map_err(|e| e.to_string())?,
+        SmtpSecurity::StartTls => SmtpTransport::starttls_relay(&email.server).map_err(|e| e.to_string())?,
+        SmtpSecurity::None => SmtpTransport::builder_dangerous(&email.server),
+    };
+
+    let mut builder = builder.port(email.port).timeout(Some(SMTP_TIMEOUT));
+
+    if let Some(ref username) = email.username {
+        let password = email.password.clone()
+            .or_else(|| std::env::var(PASSWORD_ENV).ok())
+            .unwrap_or_default();
+        builder = builder.credentials(Credentials::new(username.clone(), password));
+    }
+
+    Ok(builder.build())
+}
+
+// Asunto, texto plano y HTML con los mismos datos que el recordatorio de la terminal
+pub fn render_task_email(notification: &Notification) -> (String, String, String) {
+    let task = notification.task;
+    let heading = match notification.event {
+        TaskEvent::Reminder if notification.escalated => {
+            format!("🚨 Recordatorio insistente (pospuesta {} veces)", task.snooze_count)
+        }
+        TaskEvent::Reminder => "⏰ Recordatorio".to_string(),
+        TaskEvent::Overdue => "⌛ Tarea atrasada".to_string(),
+        TaskEvent::Added => "✅ Tarea creada".to_string(),
+        TaskEvent::Completed => "✅ Tarea completada".to_string(),
+        TaskEvent::PomodoroCompleted => "🍅 Pomodoro completado".to_string(),
+        TaskEvent::BreakEnded => "⏰ Fin del descanso".to_string(),
+    };
+
+    let subject = format!("[RusTask] {}: {}", heading, task.title);
+
+    let mut plain = format!("{}\n\n📝 Tarea: {}\n", heading, task.title);
+    let mut html = format!("<h2>{}</h2>\n<p><strong>📝 {}</strong></p>\n<ul>\n", escape_html(&heading), escape_html(&task.title));
+
+    if let Some(ref desc) = task.description {
+        plain.push_str(&format!("📄 {}\n", desc));
+        html.push_str(&format!("<li>📄 {}</li>\n", escape_html(desc)));
+    }
+
+    if let Some(scheduled) = task.scheduled_for {
+        let scheduled = scheduled.format("%d/%m/%Y %H:%M");
+        plain.push_str(&format!("🕐 Programada: {}\n", scheduled));
+        html.push_str(&format!("<li>🕐 Programada: {}</li>\n", scheduled));
+    }
+
+    if !task.tags.is_empty() {
+        let tags = task.tags.join(", ");
+        plain.push_str(&format!("🏷️ Tags: {}\n", tags));
+        html.push_str(&format!("<li>🏷️ Tags: {}</li>\n", escape_html(&tags)));
+    }
+
+    html.push_str("</ul>\n");
+
+    if matches!(notification.event, TaskEvent::Reminder) {
+        plain.push_str(&format!("\n💡 Usa 'snooze {}' para posponer\n", task.id));
+        html.push_str(&format!("<p>💡 Usa <code>snooze {}</code> para posponer</p>\n", task.id));
+    }
+
+    (subject, plain, html)
+}
+
+// Agenda del día: tareas atrasadas y programadas para hoy
+pub fn render_agenda(tasks: &[&Task], today: NaiveDate) -> (String, String, String) {
+    let mut overdue: Vec<&Task> = Vec::new();
+    let mut scheduled_today: Vec<&Task> = Vec::new();
+
+    for task in tasks.iter().filter(|t| !t.is_completed()) {
+        if let Some(scheduled) = task.scheduled_for {
+            if scheduled.date_naive() < today {
+                overdue.push(task);
+            } else if scheduled.date_naive() == today {
+                scheduled_today.push(task);
+            }
+        }
+    }
+
+    overdue.sort_by_key(|t| t.scheduled_for);
+    scheduled_today.sort_by_key(|t| t.scheduled_for);
+
+    let subject = format!("[RusTask] 📅 Agenda del {}", today.format("%d/%m/%Y"));
+    let mut plain = format!("📅 Agenda del {}\n", today.format("%d/%m/%Y"));
+    let mut html = format!("<h2>📅 Agenda del {}</h2>\n", today.format("%d/%m/%Y"));
+
+    let sections = [("⌛ Atrasadas", &overdue, "%d/%m %H:%M"), ("🕐 Hoy", &scheduled_today, "%H:%M")];
+    for (title, section, time_format) in sections {
+        if section.is_empty() {
+            continue;
+        }
+
+        plain.push_str(&format!("\n{}\n", title));
+        html.push_str(&format!("<h3>{}</h3>\n<ul>\n", title));
+
+        for task in section.iter() {
+            let time = task.scheduled_for.map(|s| s.format(time_format).to_string()).unwrap_or_default();
+            plain.push_str(&format!("  [{}] {} - {}\n", task.id, time, task.title));
+            html.push_str(&format!("<li><code>{}</code> [{}] {}</li>\n", time, task.id, escape_html(&task.title)));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if overdue.is_empty() && scheduled_today.is_empty() {
+        plain.push_str("\n🎉 No hay tareas programadas para hoy\n");
+        html.push_str("<p>🎉 No hay tareas programadas para hoy</p>\n");
+    }
+
+    (subject, plain, html)
+}
+
+// ¿Toca enviar la agenda diaria? (una vez por día, a partir de la hora configurada)
+pub fn is_agenda_due(config: &Config, now: DateTime<Local>) -> bool {
+    let agenda_time = match config.email {
+        Some(ref email) => match email.agenda_time {
+            Some(time) => time,
+            None => return false,
+        },
+        None => return false,
+    };
+
+    now.time() >= agenda_time && config.last_agenda_sent != Some(now.date_naive())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    // Servidor SMTP mínimo que acepta un mensaje y retorna el contenido de DATA
+    fn smtp_sink() -> (u16, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut data = String::new();
+            let mut in_data = false;
+
+            writer.write_all(b"220 sink ESMTP\r\n").unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 {
+                if in_data {
+                    if line == ".\r\n" {
+                        in_data = false;
+                        writer.write_all(b"250 OK\r\n").unwrap();
+                    } else {
+                        data.push_str(&line);
+                    }
+                } else {
+                    let command = line.to_uppercase();
+                    let reply: &[u8] = if command.starts_with("EHLO") {
+                        b"250 sink\r\n"
+                    } else if command.starts_with("DATA") {
+                        in_data = true;
+                        b"354 Go ahead\r\n"
+                    } else if command.starts_with("QUIT") {
+                        writer.write_all(b"221 Bye\r\n").unwrap();
+                        break;
+                    } else {
+                        b"250 OK\r\n"
+                    };
+                    writer.write_all(reply).unwrap();
+                }
+                line.clear();
+            }
+            data
+        });
+
+        (port, handle)
+    }
+
+    fn email_config(port: u16) -> EmailConfig {
+        EmailConfig {
+            server: "127.0.0.1".to_string(),
+            port,
+            security: SmtpSecurity::None,
+            username: None,
+            password: None,
+            from: "rustask@example.com".to_string(),
+            to: vec!["yo@example.com".to_string()],
+            events: vec![TaskEvent::Reminder],
+            agenda_time: None,
+        }
+    }
+
+    #[test]
+    fn test_reminder_email_is_delivered() {
+        let (port, sink) = smtp_sink();
+        let mut task = Task::new(4, "Pagar <alquiler>".to_string(), Some("Antes del 5".to_string()));
+        task.add_tag("casa".to_string());
+        let notification = Notification { event: TaskEvent::Reminder, task: &task, escalated: false };
+
+        let (subject, plain, html) = render_task_email(&notification);
+        send_email(&email_config(port), &subject, plain, html).unwrap();
+
+        let data = sink.join().unwrap();
+        assert!(data.contains("To: yo@example.com"));
+        assert!(data.contains("multipart/alternative"));
+        assert!(data.contains("text/html"));
+        assert!(data.contains("Pagar &lt;alquiler&gt;"));
+    }
+
+    #[test]
+    fn test_agenda_groups_overdue_and_today() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 4).unwrap();
+        let at = |d: u32, h: u32| {
+            let dt = NaiveDate::from_ymd_opt(2025, 6, d).unwrap().and_hms_opt(h, 0, 0).unwrap();
+            dt.and_local_timezone(Local).unwrap()
+        };
+
+        let mut late = Task::new(1, "Atrasada".to_string(), None);
+        late.schedule_for(at(3, 10));
+        let mut now = Task::new(2, "De hoy".to_string(), None);
+        now.schedule_for(at(4, 18));
+        let mut later = Task::new(3, "Mañana".to_string(), None);
+        later.schedule_for(at(5, 9));
+
+        let (_, plain, _) = render_agenda(&[&late, &now, &later], today);
+        assert!(plain.contains("[1] 03/06 10:00 - Atrasada"));
+        assert!(plain.contains("[2] 18:00 - De hoy"));
+        assert!(!plain.contains("Mañana"));
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use crate::task::Task;
+
+// Estimaciones comparadas con el tiempo registrado con start/stop
+
+const UNTAGGED: &str = "Sin tag";
+
+// Estimado y real de un grupo de tareas completadas
+#[derive(Debug, PartialEq)]
+pub struct Accuracy {
+    pub group: String,
+    pub tasks: usize,
+    pub estimated: Duration,
+    pub actual: Duration,
+}
+
+impl Accuracy {
+    fn new(group: &str) -> Self {
+        Self { group: group.to_string(), tasks: 0, estimated: Duration::zero(), actual: Duration::zero() }
+    }
+
+    fn add(&mut self, estimated: Duration, actual: Duration) {
+        self.tasks += 1;
+        self.estimated += estimated;
+        self.actual += actual;
+    }
+
+    // Porcentaje de desvío: positivo si se tardó más de lo estimado
+    pub fn deviation(&self) -> f64 {
+        let estimated = self.estimated.num_minutes() as f64;
+        if estimated == 0.0 {
+            return 0.0;
+        }
+        (self.actual.num_minutes() as f64 - estimated) / estimated * 100.0
+    }
+}
+
+// Por tag (una tarea con varios tags cuenta en cada uno) y el total. Solo entran
+// las tareas completadas con estimación y tiempo registrado.
+pub fn accuracy_by_tag(tasks: &[Task], now: DateTime<Utc>) -> (Vec<Accuracy>, Accuracy) {
+    let mut by_tag: BTreeMap<&str, Accuracy> = BTreeMap::new();
+    let mut untagged = Accuracy::new(UNTAGGED);
+    let mut total = Accuracy::new("Total");
+
+    for task in tasks.iter().filter(|t| t.is_completed()) {
+        let Some(estimated) = task.estimate() else { continue };
+        let actual = task.tracked_time(now);
+        if actual.is_zero() {
+            continue;
+        }
+
+        total.add(estimated, actual);
+        if task.tags.is_empty() {
+            untagged.add(estimated, actual);
+        }
+        for tag in &task.tags {
+            by_tag.entry(tag).or_insert_with(|| Accuracy::new(tag)).add(estimated, actual);
+        }
+    }
+
+    let mut groups: Vec<Accuracy> = by_tag.into_values().collect();
+    if untagged.tasks > 0 {
+        groups.push(untagged);
+    }
+    (groups, total)
+}
+
+// Trabajo que falta en las tareas pendientes programadas para `day`:
+// lo estimado menos lo ya registrado
+pub fn scheduled_load(tasks: &[Task], day: NaiveDate, now: DateTime<Utc>) -> Duration {
+    tasks.iter()
+        .filter(|t| !t.is_completed())
+        .filter(|t| t.scheduled_for.is_some_and(|s| s.date_naive() == day))
+        .filter_map(|t| t.estimate().map(|estimate| (estimate - t.tracked_time(now)).max(Duration::zero())))
+        .sum()
+}
+
+// Horas disponibles por día (config) como Duration
+pub fn available_per_day(hours: f64) -> Duration {
+    Duration::minutes((hours * 60.0).round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use crate::task::TimeEntry;
+
+    fn task(id: u64, tags: &[&str], estimate: i64, worked: i64) -> Task {
+        let mut task = Task::new_with_tags(id, format!("Tarea {}", id), None, tags.iter().map(|t| t.to_string()).collect());
+        task.estimate_minutes = Some(estimate);
+        if worked > 0 {
+            let start = Utc::now() - Duration::days(1);
+            task.time_entries.push(TimeEntry { start, end: Some(start + Duration::minutes(worked)) });
+        }
+        task
+    }
+
+    #[test]
+    fn test_accuracy_by_tag() {
+        let mut tasks = vec![task(1, &["web"], 60, 105), task(2, &["web", "api"], 120, 120), task(3, &[], 30, 15), task(4, &["web"], 60, 0)];
+        for task in &mut tasks[..3] {
+            task.complete();
+        }
+        // Sin terminar: no cuenta
+        tasks.push(task(5, &["api"], 60, 200));
+
+        let (groups, total) = accuracy_by_tag(&tasks, Utc::now());
+        let summary: Vec<(&str, usize, f64)> = groups.iter().map(|g| (g.group.as_str(), g.tasks, g.deviation())).collect();
+        assert_eq!(summary, vec![("api", 1, 0.0), ("web", 2, 25.0), ("Sin tag", 1, -50.0)]);
+        assert_eq!(total.tasks, 3);
+        assert_eq!(total.estimated, Duration::minutes(210));
+        assert_eq!(total.actual, Duration::minutes(240));
+    }
+
+    #[test]
+    fn test_scheduled_load_and_capacity() {
+        let day = NaiveDate::from_ymd_opt(2025, 6, 4).unwrap();
+        let at = |hour| Local.from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap()).unwrap();
+
+        let mut tasks = vec![task(1, &[], 300, 60), task(2, &[], 240, 0), task(3, &[], 600, 0)];
+        tasks[0].schedule_for(at(9));
+        tasks[1].schedule_for(at(14));
+        // Otro día
+        tasks[2].schedule_for(at(10) + Duration::days(1));
+
+        // La tarea 1 ya tiene una hora registrada
+        assert_eq!(scheduled_load(&tasks, day, Utc::now()), Duration::minutes(240 + 240));
+        assert!(scheduled_load(&tasks, day, Utc::now()) <= available_per_day(8.0));
+        assert!(scheduled_load(&tasks, day.succ_opt().unwrap(), Utc::now()) > available_per_day(8.0));
+
+        tasks[1].complete();
+        assert_eq!(scheduled_load(&tasks, day, Utc::now()), Duration::minutes(240));
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+use crate::migrations;
+use crate::task::Task;
+
+// Registro de eventos: un archivo donde cada línea es un cambio en las tareas.
+//   {"seq":1,"at":"...","type":"added","task":{...}}
+//   {"seq":2,"at":"...","type":"updated","id":1,"changes":{"title":{"before":"a","after":"b"}}}
+//   {"seq":3,"at":"...","type":"deleted","task":{...}}
+//   {"seq":4,"at":"...","type":"snapshot","version":2,"next_id":2,"tasks":[...]}
+// El estado se reconstruye desde la última snapshot, así que el tiempo de carga
+// no crece con el historial. 'compact' reescribe el archivo con una sola snapshot.
+
+// Cada cuántos eventos se agrega una snapshot
+const SNAPSHOT_EVERY: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    Added { task: Task },
+    Updated {
+        id: u64,
+        // Los eventos anteriores no lo guardaban
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        uuid: Option<Uuid>,
+        changes: BTreeMap<String, Change>,
+    },
+    Deleted { task: Task },
+    Snapshot { version: u64, next_id: u64, tasks: Vec<Task> },
+}
+
+// Valor de un campo antes y después del cambio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub before: Value,
+    pub after: Value,
+}
+
+// Estado reconstruido a partir del registro
+#[derive(Debug, Default)]
+pub struct Replay {
+    pub tasks: Vec<Task>,
+    pub next_id: u64,
+    pub seq: u64,
+    // Eventos desde la última snapshot
+    pub since_snapshot: usize,
+}
+
+// Eventos que llevan de `before` a `after`. Una tarea nueva que reutiliza el ID
+// de otra (todo.txt editado a mano) es una baja y un alta, no un cambio.
+pub fn diff(before: &[Task], after: &[Task]) -> Vec<EventKind> {
+    let previous: HashMap<u64, &Task> = before.iter().map(|t| (t.id, t)).collect();
+    let same = |a: &Task, b: &Task| a.id == b.id && a.uuid == b.uuid;
+
+    // Las bajas primero, para que al reproducir no borren la tarea que ocupa su ID
+    let mut events: Vec<EventKind> = before.iter()
+        .filter(|t| !after.iter().any(|a| same(a, t)))
+        .map(|t| EventKind::Deleted { task: t.clone() })
+        .collect();
+
+    for task in after {
+        match previous.get(&task.id).filter(|old| same(old, task)) {
+            None => events.push(EventKind::Added { task: task.clone() }),
+            Some(old) => {
+                let changes = field_changes(old, task);
+                if !changes.is_empty() {
+                    events.push(EventKind::Updated { id: task.id, uuid: Some(task.uuid), changes });
+                }
+            }
+        }
+    }
+
+    events
+}
+
+fn field_changes(before: &Task, after: &Task) -> BTreeMap<String, Change> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) = (serde_json::to_value(before), serde_json::to_value(after)) else {
+        return BTreeMap::new();
+    };
+
+    after.into_iter()
+        .filter_map(|(field, value)| {
+            let old = before.get(&field).cloned().unwrap_or(Value::Null);
+            (old != value).then_some((field, Change { before: old, after: value }))
+        })
+        .collect()
+}
+
+fn apply(replay: &mut Replay, event: Event) -> Result<(), String> {
+    match event.kind {
+        EventKind::Added { task } => {
+            replay.next_id = replay.next_id.max(task.id + 1);
+            replay.tasks.retain(|t| t.id != task.id);
+            replay.tasks.push(task);
+        }
+        EventKind::Updated { id, changes, .. } => {
+            let task = replay.tasks.iter_mut()
+                .find(|t| t.id == id)
+                .ok_or_else(|| format!("evento {}: no existe la tarea {}", event.seq, id))?;
+
+            let mut value = serde_json::to_value(&*task).map_err(|e| e.to_string())?;
+            for (field, change) in changes {
+                value[field] = change.after;
+            }
+            *task = serde_json::from_value(value).map_err(|e| format!("evento {}: {}", event.seq, e))?;
+        }
+        EventKind::Deleted { task } => replay.tasks.retain(|t| t.id != task.id),
+        EventKind::Snapshot { next_id, tasks, .. } => {
+            replay.tasks = tasks;
+            replay.next_id = next_id;
+        }
+    }
+
+    replay.seq = event.seq;
+    Ok(())
+}
+
+// Las snapshots pasan por las migraciones igual que tasks.json
+fn parse_snapshot(line: &str) -> Result<Event, String> {
+    let mut value: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    let envelope = json!({
+        "version": value["version"].clone(),
+        "next_id": value["next_id"].clone(),
+        "tasks": value["tasks"].take(),
+    });
+    value["tasks"] = migrations::migrate(envelope)?["tasks"].take();
+    value["version"] = json!(migrations::CURRENT_VERSION);
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+fn is_snapshot(line: &str) -> bool {
+    line.contains(r#""type":"snapshot""#)
+}
+
+pub fn replay(path: &str) -> Result<Replay, io::Error> {
+    let mut replay = Replay { next_id: 1, ..Replay::default() };
+    if !Path::new(path).exists() {
+        return Ok(replay);
+    }
+
+    let data = fs::read_to_string(path)?;
+    let lines: Vec<&str> = data.lines().filter(|l| !l.trim().is_empty()).collect();
+
+    // Empezar por la última snapshot; lo anterior ya está incluido en ella
+    let start = lines.iter().rposition(|l| is_snapshot(l)).unwrap_or(0);
+
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        let event = if is_snapshot(line) {
+            parse_snapshot(line)
+        } else {
+            serde_json::from_str(line).map_err(|e| e.to_string())
+        };
+        let event = event.map_err(|e| io::Error::other(format!("línea {}: {}", i + 1, e)))?;
+
+        if !matches!(event.kind, EventKind::Snapshot { .. }) {
+            replay.since_snapshot += 1;
+        }
+        apply(&mut replay, event).map_err(io::Error::other)?;
+    }
+
+    Ok(replay)
+}
+
+// Todos los eventos del archivo, incluidos los anteriores a la última snapshot
+pub fn read_events(path: &str) -> Result<Vec<Event>, io::Error> {
+    if !Path::new(path).exists() {
+        return Ok(Vec::new());
+    }
+
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            let event = if is_snapshot(line) { parse_snapshot(line) } else { serde_json::from_str(line).map_err(|e| e.to_string()) };
+            event.map_err(|e| io::Error::other(format!("línea {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+// Agrega los eventos al final del archivo, y una snapshot cada SNAPSHOT_EVERY eventos
+pub fn append(path: &str, replay: &mut Replay, mut events: Vec<EventKind>, tasks: &[Task], next_id: u64) -> Result<(), io::Error> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    replay.since_snapshot += events.len();
+    if replay.since_snapshot >= SNAPSHOT_EVERY {
+        events.push(snapshot(tasks, next_id));
+        replay.since_snapshot = 0;
+    }
+    write_events(path, &mut replay.seq, events)?;
+
+    replay.tasks = tasks.to_vec();
+    replay.next_id = next_id;
+    Ok(())
+}
+
+// Escribe los eventos numerándolos a partir de `seq`, que queda en el último
+pub fn write_events(path: &str, seq: &mut u64, events: Vec<EventKind>) -> Result<(), io::Error> {
+    let mut lines = String::new();
+    for kind in events {
+        *seq += 1;
+        let event = Event { seq: *seq, at: Utc::now(), kind };
+        lines.push_str(&serde_json::to_string(&event).map_err(io::Error::other)?);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(lines.as_bytes())
+}
+
+fn snapshot(tasks: &[Task], next_id: u64) -> EventKind {
+    EventKind::Snapshot { version: migrations::CURRENT_VERSION, next_id, tasks: tasks.to_vec() }
+}
+
+// Reescribe el archivo con una sola snapshot (se pierde el historial anterior)
+pub fn compact(path: &str, replay: &mut Replay, tasks: &[Task], next_id: u64) -> Result<(), io::Error> {
+    replay.seq += 1;
+    let event = Event { seq: replay.seq, at: Utc::now(), kind: snapshot(tasks, next_id) };
+    let line = serde_json::to_string(&event).map_err(io::Error::other)? + "\n";
+
+    // Escribir aparte y renombrar, para no quedar con un archivo a medias
+    let temp = format!("{}.tmp", path);
+    fs::write(&temp, line)?;
+    fs::rename(&temp, path)?;
+
+    replay.tasks = tasks.to_vec();
+    replay.next_id = next_id;
+    replay.since_snapshot = 0;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("rustask-eventlog-{}-{}.log", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_diff_and_replay_rebuild_state() {
+        let path = log_path("replay");
+        let mut state = replay(&path).unwrap();
+
+        let mut tasks = vec![Task::new(1, "Uno".to_string(), None), Task::new(2, "Dos".to_string(), None)];
+        append(&path, &mut state, diff(&[], &tasks), &tasks, 3).unwrap();
+
+        let before = tasks.clone();
+        tasks[0].title = "Uno editada".to_string();
+        tasks[0].complete();
+        tasks.remove(1);
+        let events = diff(&before, &tasks);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], EventKind::Deleted { ref task } if task.id == 2));
+        if let EventKind::Updated { ref changes, .. } = events[1] {
+            assert_eq!(changes["title"], Change { before: json!("Uno"), after: json!("Uno editada") });
+            assert!(changes.contains_key("status") && changes.contains_key("completed_at"));
+        } else {
+            panic!("se esperaba un evento updated");
+        }
+        append(&path, &mut state, events, &tasks, 3).unwrap();
+
+        let rebuilt = replay(&path).unwrap();
+        assert_eq!(rebuilt.seq, 4);
+        assert_eq!(rebuilt.next_id, 3);
+        assert_eq!(rebuilt.tasks.len(), 1);
+        assert_eq!(rebuilt.tasks[0].title, "Uno editada");
+        assert!(rebuilt.tasks[0].is_completed());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_compact_keeps_state_and_drops_history() {
+        let path = log_path("compact");
+        let mut state = replay(&path).unwrap();
+        let mut tasks = vec![Task::new(1, "Uno".to_string(), None)];
+        append(&path, &mut state, diff(&[], &tasks), &tasks, 2).unwrap();
+
+        let before = tasks.clone();
+        tasks[0].add_tag("casa".to_string());
+        append(&path, &mut state, diff(&before, &tasks), &tasks, 2).unwrap();
+        assert_eq!(read_events(&path).unwrap().len(), 2);
+
+        compact(&path, &mut state, &tasks, 2).unwrap();
+        let events = read_events(&path).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0].kind, EventKind::Snapshot { .. }));
+
+        let rebuilt = replay(&path).unwrap();
+        assert_eq!(rebuilt.tasks[0].tags, vec!["casa"]);
+        assert_eq!(rebuilt.since_snapshot, 0);
+
+        fs::remove_file(path).unwrap();
+    }
+}
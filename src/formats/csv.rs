@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use uuid::Uuid;
+use chrono::Utc;
+use crate::task::{Task, TaskStatus};
+use super::{parse_iso_datetime, parse_iso_datetime_utc, parse_priority, parse_status, priority_name, status_name, ImportPlan};
+
+// Columnas en el orden de exportación; también son los nombres de campo de --map
+pub const FIELDS: [&str; 20] = [
+    "id", "uuid", "title", "description", "tags", "status", "priority", "project", "due", "created_at",
+    "completed_at", "scheduled_for", "reminder_sent", "snoozed_until", "snooze_count", "overdue_notified",
+    "estimate_minutes", "last_reviewed", "time_entries", "pomodoros",
+];
+
+const TAG_SEPARATOR: &str = ";";
+
+pub fn export<W: Write>(tasks: &[Task], writer: W) -> Result<(), String> {
+    let mut csv_writer = ::csv::Writer::from_writer(writer);
+    csv_writer.write_record(FIELDS).map_err(|e| e.to_string())?;
+
+    for task in tasks {
+        let record = [
+            task.id.to_string(),
+            task.uuid.to_string(),
+            task.title.clone(),
+            task.description.clone().unwrap_or_default(),
+            task.tags.join(TAG_SEPARATOR),
+            status_name(&task.status).to_string(),
+            task.priority.map(|p| priority_name(p).to_string()).unwrap_or_default(),
+            task.project.clone().unwrap_or_default(),
+            task.due.map(|d| d.to_rfc3339()).unwrap_or_default(),
+            task.created_at.to_rfc3339(),
+            task.completed_at.map(|d| d.to_rfc3339()).unwrap_or_default(),
+            task.scheduled_for.map(|d| d.to_rfc3339()).unwrap_or_default(),
+            task.reminder_sent.to_string(),
+            task.snoozed_until.map(|d| d.to_rfc3339()).unwrap_or_default(),
+            task.snooze_count.to_string(),
+            task.overdue_notified.to_string(),
+            task.estimate_minutes.map(|m| m.to_string()).unwrap_or_default(),
+            task.last_reviewed.map(|d| d.to_rfc3339()).unwrap_or_default(),
+            json_cell(&task.time_entries)?,
+            json_cell(&task.pomodoros)?,
+        ];
+        csv_writer.write_record(&record).map_err(|e| e.to_string())?;
+    }
+
+    csv_writer.flush().map_err(|e| e.to_string())
+}
+
+// Lee un CSV con cabecera. Las columnas se asocian a campos por nombre, o con
+// `mapping` (columna → campo) para archivos con otras cabeceras.
+pub fn import<R: Read>(reader: R, mapping: &[(String, String)], existing: &[Task]) -> Result<ImportPlan, String> {
+    let mut csv_reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(reader);
+
+    let headers = csv_reader.headers().map_err(|e| e.to_string())?.clone();
+    let columns = resolve_columns(&headers, mapping)?;
+    let with_created_at = columns.values().any(|field| *field == "created_at");
+
+    let mut tasks = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, record) in csv_reader.records().enumerate() {
+        // Fila 1 es la cabecera
+        let row = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(format!("fila {}: {}", row, e));
+                continue;
+            }
+        };
+
+        let values: HashMap<&str, &str> = columns.iter()
+            .filter_map(|(index, field)| record.get(*index).map(|value| (*field, value)))
+            .collect();
+
+        match parse_task(&values) {
+            Ok(task) => tasks.push(task),
+            Err(e) => errors.push(format!("fila {}: {}", row, e)),
+        }
+    }
+
+    Ok(ImportPlan::build(existing, tasks, errors, with_created_at))
+}
+
+// Índice de columna → nombre de campo
+fn resolve_columns(headers: &::csv::StringRecord, mapping: &[(String, String)]) -> Result<HashMap<usize, &'static str>, String> {
+    for (_, field) in mapping {
+        if !FIELDS.contains(&field.as_str()) {
+            return Err(format!("Campo desconocido '{}'. Campos: {}", field, FIELDS.join(", ")));
+        }
+    }
+
+    let mut columns = HashMap::new();
+    for (index, header) in headers.iter().enumerate() {
+        let field = mapping.iter()
+            .find(|(column, _)| column == header)
+            .map(|(_, field)| field.to_lowercase())
+            .unwrap_or_else(|| header.to_lowercase());
+
+        if let Some(field) = FIELDS.iter().find(|f| **f == field) {
+            columns.insert(index, *field);
+        }
+    }
+
+    if !columns.values().any(|field| *field == "title") {
+        return Err("El CSV no tiene columna 'title' (usa --map \"Columna=title\")".to_string());
+    }
+
+    Ok(columns)
+}
+
+fn parse_task(values: &HashMap<&str, &str>) -> Result<Task, String> {
+    // Valor no vacío de un campo
+    let get = |field: &str| values.get(field).copied().filter(|v| !v.is_empty());
+
+    let title = get("title").ok_or("título vacío")?.to_string();
+    let mut task = Task::new(0, title, get("description").map(|d| d.to_string()));
+
+    if let Some(tags) = get("tags") {
+        for tag in tags.split(TAG_SEPARATOR).map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            task.add_tag(tag.to_string());
+        }
+    }
+
+    if let Some(uuid) = get("uuid") {
+        task.uuid = Uuid::parse_str(uuid).map_err(|_| format!("uuid inválido '{}'", uuid))?;
+    }
+    if let Some(status) = get("status") {
+        task.status = parse_status(status)?;
+    }
+    if let Some(priority) = get("priority") {
+        task.priority = Some(parse_priority(priority)?);
+    }
+    if let Some(project) = get("project") {
+        task.project = Some(project.to_string());
+    }
+    if let Some(due) = get("due") {
+        task.due = Some(parse_iso_datetime(due)?);
+    }
+    if let Some(created_at) = get("created_at") {
+        task.created_at = parse_iso_datetime_utc(created_at)?;
+    }
+    if let Some(completed_at) = get("completed_at") {
+        task.completed_at = Some(parse_iso_datetime_utc(completed_at)?);
+    }
+    if let Some(scheduled_for) = get("scheduled_for") {
+        task.scheduled_for = Some(parse_iso_datetime(scheduled_for)?);
+    }
+    if let Some(snoozed_until) = get("snoozed_until") {
+        task.snoozed_until = Some(parse_iso_datetime(snoozed_until)?);
+    }
+    if let Some(reminder_sent) = get("reminder_sent") {
+        task.reminder_sent = parse_bool(reminder_sent)?;
+    }
+    if let Some(overdue_notified) = get("overdue_notified") {
+        task.overdue_notified = parse_bool(overdue_notified)?;
+    }
+    if let Some(snooze_count) = get("snooze_count") {
+        task.snooze_count = snooze_count.parse()
+            .map_err(|_| format!("snooze_count inválido '{}'", snooze_count))?;
+    }
+    if let Some(estimate) = get("estimate_minutes") {
+        task.estimate_minutes = Some(estimate.parse()
+            .map_err(|_| format!("estimate_minutes inválido '{}'", estimate))?);
+    }
+    if let Some(last_reviewed) = get("last_reviewed") {
+        task.last_reviewed = Some(parse_iso_datetime_utc(last_reviewed)?);
+    }
+    if let Some(time_entries) = get("time_entries") {
+        task.time_entries = serde_json::from_str(time_entries)
+            .map_err(|e| format!("time_entries inválido: {}", e))?;
+    }
+    if let Some(pomodoros) = get("pomodoros") {
+        task.pomodoros = serde_json::from_str(pomodoros)
+            .map_err(|e| format!("pomodoros inválido: {}", e))?;
+    }
+
+    // Estado y fecha de completado deben coincidir: stats y gráficos cuentan por
+    // la fecha. Sin columna de estado, la fecha indica que está completada.
+    if get("status").is_none() && task.completed_at.is_some() {
+        task.status = TaskStatus::Completed;
+    }
+    match task.status {
+        TaskStatus::Completed => task.completed_at = task.completed_at.or(Some(Utc::now())),
+        TaskStatus::Pending => task.completed_at = None,
+    }
+
+    Ok(task)
+}
+
+// Listas (tiempo registrado, pomodoros) como JSON en una celda; vacía si no hay nada
+fn json_cell<T: serde::Serialize>(items: &[T]) -> Result<String, String> {
+    if items.is_empty() {
+        return Ok(String::new());
+    }
+    serde_json::to_string(items).map_err(|e| e.to_string())
+}
+
+fn parse_bool(s: &str) -> Result<bool, String> {
+    match s.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "si" | "sí" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        other => Err(format!("valor booleano inválido '{}'", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TimeEntry;
+
+    #[test]
+    fn test_export_import_roundtrip_detects_duplicates() {
+        let mut task = Task::new_with_tags(1, "Comprar, pan".to_string(), Some("En \"la\" esquina".to_string()),
+                                           vec!["casa".to_string(), "urgente".to_string()]);
+        task.complete();
+        task.estimate_minutes = Some(45);
+        task.last_reviewed = Some(task.created_at);
+        task.time_entries.push(TimeEntry { start: task.created_at, end: Some(task.created_at + chrono::Duration::minutes(25)) });
+        task.pomodoros.push(task.created_at);
+        let other = Task::new(2, "Otra".to_string(), None);
+
+        let mut output = Vec::new();
+        export(&[task.clone(), other], &mut output).unwrap();
+
+        // Importar en un almacenamiento vacío: todo es nuevo y se conserva
+        let plan = import(output.as_slice(), &[], &[]).unwrap();
+        assert!(plan.errors.is_empty());
+        assert_eq!(plan.new_tasks.len(), 2);
+        let imported = &plan.new_tasks[0];
+        assert_eq!(imported.title, task.title);
+        assert_eq!(imported.description, task.description);
+        assert_eq!(imported.tags, task.tags);
+        assert!(imported.is_completed());
+        assert_eq!(imported.created_at.timestamp(), task.created_at.timestamp());
+        assert_eq!(imported.uuid, task.uuid);
+        assert_eq!(imported.estimate_minutes, Some(45));
+        assert_eq!(imported.last_reviewed.map(|d| d.timestamp()), Some(task.created_at.timestamp()));
+        assert_eq!(imported.time_entries, task.time_entries);
+        assert_eq!(imported.pomodoros, task.pomodoros);
+
+        // Importar otra vez sobre la tarea existente: es un duplicado
+        let plan = import(output.as_slice(), &[], &[task]).unwrap();
+        assert_eq!(plan.new_tasks.len(), 1);
+        assert_eq!(plan.duplicates.len(), 1);
+    }
+
+    #[test]
+    fn test_import_with_column_mapping_and_errors() {
+        let data = "Nombre,Etiquetas,Fecha\nLlamar,trabajo;tel,2025-06-04 10:30\n,x,\nPagar,,mañana\n";
+        let mapping = vec![
+            ("Nombre".to_string(), "title".to_string()),
+            ("Etiquetas".to_string(), "tags".to_string()),
+            ("Fecha".to_string(), "scheduled_for".to_string()),
+        ];
+
+        let plan = import(data.as_bytes(), &mapping, &[]).unwrap();
+        assert_eq!(plan.new_tasks.len(), 1);
+        assert_eq!(plan.new_tasks[0].tags, vec!["trabajo", "tel"]);
+        assert!(plan.new_tasks[0].scheduled_for.is_some());
+        assert_eq!(plan.errors.len(), 2);
+        assert!(plan.errors[0].starts_with("fila 3"));
+
+        assert!(import("a,b\n1,2\n".as_bytes(), &[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_import_reconciles_status_and_completed_at() {
+        let data = "title,status,completed_at
+                    Sin fecha,completed,
+                    Pendiente,pending,2025-06-04T10:00:00Z
+                    Solo fecha,,2025-06-04T10:00:00Z
+";
+
+        let plan = import(data.as_bytes(), &[], &[]).unwrap();
+        let [no_date, pending, only_date] = plan.new_tasks.as_slice() else { panic!("se esperaban 3 tareas") };
+        assert!(no_date.is_completed() && no_date.completed_at.is_some());
+        assert!(!pending.is_completed() && pending.completed_at.is_none());
+        assert!(only_date.is_completed());
+        assert_eq!(only_date.completed_at.map(|d| d.to_rfc3339()).as_deref(), Some("2025-06-04T10:00:00+00:00"));
+    }
+}
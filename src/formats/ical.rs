@@ -0,0 +1,533 @@
+use std::io::Write;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use uuid::Uuid;
+use crate::task::{Priority, Task, TaskStatus};
+use super::ImportPlan;
+
+// iCalendar (RFC 5545). Cada tarea con fecha se exporta como un VTODO:
+//   scheduled_for  DTSTART y un VALARM que suena a esa hora
+//   due            DUE
+//   tags           CATEGORIES
+//   project        X-RUSTASK-PROJECT
+// rustask no tiene tareas recurrentes, así que no se genera RRULE (y al
+// importar se ignora).
+// Al importar se aceptan VTODO y VEVENT; la hora de la primera alarma (o, si
+// no hay, DTSTART) pasa a ser el recordatorio.
+
+const PRODID: &str = "-//rustask//rustask//ES";
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const LOCAL_FORMAT: &str = "%Y%m%dT%H%M%S";
+const DATE_FORMAT: &str = "%Y%m%d";
+// Largo máximo de línea en octetos, sin contar el CRLF
+const MAX_LINE: usize = 75;
+
+pub fn export<W: Write>(tasks: &[Task], mut writer: W) -> Result<(), String> {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+    ];
+
+    let now = Utc::now();
+    for task in tasks.iter().filter(|t| t.scheduled_for.is_some() || t.due.is_some()) {
+        lines.extend(format_vtodo(task, now));
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    for line in lines {
+        write!(writer, "{}\r\n", fold_line(&line)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn format_vtodo(task: &Task, now: DateTime<Utc>) -> Vec<String> {
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        // Estable entre exportaciones para que el calendario actualice en vez de duplicar
+        format!("UID:{}", task.uuid),
+        format!("DTSTAMP:{}", now.format(UTC_FORMAT)),
+        format!("CREATED:{}", task.created_at.format(UTC_FORMAT)),
+        format!("SUMMARY:{}", escape_text(&task.title)),
+    ];
+
+    if let Some(ref description) = task.description {
+        lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|t| escape_text(t)).collect();
+        lines.push(format!("CATEGORIES:{}", tags.join(",")));
+    }
+    if let Some(ref project) = task.project {
+        lines.push(format!("X-RUSTASK-PROJECT:{}", escape_text(project)));
+    }
+    if let Some(priority) = task.priority {
+        lines.push(format!("PRIORITY:{}", priority_number(priority)));
+    }
+    if let Some(scheduled) = task.scheduled_for {
+        lines.push(format!("DTSTART:{}", format_utc(scheduled)));
+    }
+    if let Some(due) = task.due {
+        lines.push(format!("DUE:{}", format_utc(due)));
+    }
+
+    if task.is_completed() {
+        lines.push("STATUS:COMPLETED".to_string());
+        if let Some(completed_at) = task.completed_at {
+            lines.push(format!("COMPLETED:{}", completed_at.format(UTC_FORMAT)));
+        }
+    } else {
+        lines.push("STATUS:NEEDS-ACTION".to_string());
+
+        // Recordatorio a la hora programada
+        if task.scheduled_for.is_some() {
+            lines.extend([
+                "BEGIN:VALARM".to_string(),
+                "ACTION:DISPLAY".to_string(),
+                format!("DESCRIPTION:{}", escape_text(&task.title)),
+                "TRIGGER;RELATED=START:PT0S".to_string(),
+                "END:VALARM".to_string(),
+            ]);
+        }
+    }
+
+    lines.push("END:VTODO".to_string());
+    lines
+}
+
+fn format_utc(dt: DateTime<Local>) -> String {
+    dt.with_timezone(&Utc).format(UTC_FORMAT).to_string()
+}
+
+// PRIORITY de iCalendar: 1-4 alta, 5 media, 6-9 baja (0 = sin prioridad)
+fn priority_number(priority: Priority) -> u8 {
+    match priority {
+        Priority::High => 1,
+        Priority::Medium => 5,
+        Priority::Low => 9,
+    }
+}
+
+fn priority_from_number(n: u8) -> Option<Priority> {
+    match n {
+        1..=4 => Some(Priority::High),
+        5 => Some(Priority::Medium),
+        6..=9 => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+// Corta líneas largas en trozos de 75 octetos; las continuaciones empiezan con un espacio
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE {
+            folded.push_str("\r\n ");
+            // El espacio inicial cuenta para el largo
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded
+}
+
+// Propiedad de una línea "NOMBRE;PARAM=valor:VALOR"
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    // El primer ':' fuera de comillas separa nombre y parámetros del valor
+    let mut in_quotes = false;
+    let colon = line.char_indices().find(|(_, c)| {
+        if *c == '"' {
+            in_quotes = !in_quotes;
+        }
+        *c == ':' && !in_quotes
+    })?.0;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.to_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.to_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+
+    Some(Property { name, params, value: value.to_string() })
+}
+
+// Un VTODO o VEVENT con sus alarmas
+struct Component {
+    kind: String,
+    properties: Vec<Property>,
+    alarms: Vec<Vec<Property>>,
+}
+
+impl Component {
+    fn get(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+}
+
+// Une las líneas plegadas (las que empiezan con espacio o tab continúan la anterior)
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if !line.is_empty() => lines.push(line.to_string()),
+            _ => {}
+        }
+    }
+    lines
+}
+
+fn parse_components(content: &str) -> Result<Vec<Component>, String> {
+    let mut components = Vec::new();
+    let mut current: Option<Component> = None;
+    let mut alarm: Option<Vec<Property>> = None;
+
+    for line in unfold(content) {
+        let Some(property) = parse_property(&line) else {
+            continue;
+        };
+        let value = property.value.to_uppercase();
+
+        match (property.name.as_str(), value.as_str()) {
+            ("BEGIN", "VTODO") | ("BEGIN", "VEVENT") => {
+                current = Some(Component { kind: value, properties: Vec::new(), alarms: Vec::new() });
+            }
+            ("BEGIN", "VALARM") if current.is_some() => alarm = Some(Vec::new()),
+            ("END", "VALARM") => {
+                if let (Some(properties), Some(component)) = (alarm.take(), current.as_mut()) {
+                    component.alarms.push(properties);
+                }
+            }
+            ("END", "VTODO") | ("END", "VEVENT") => {
+                if let Some(component) = current.take() {
+                    components.push(component);
+                }
+            }
+            _ => {
+                if let Some(ref mut properties) = alarm {
+                    properties.push(property);
+                } else if let Some(ref mut component) = current {
+                    component.properties.push(property);
+                }
+            }
+        }
+    }
+
+    if !content.contains("BEGIN:VCALENDAR") {
+        return Err("no es un archivo iCalendar (falta BEGIN:VCALENDAR)".to_string());
+    }
+
+    Ok(components)
+}
+
+// "20250604T100000Z" (UTC), "20250604T100000" (hora local) o "20250604".
+// Con TZID se toma la hora como local: no tenemos base de datos de zonas horarias.
+fn parse_datetime(property: &Property) -> Result<DateTime<Local>, String> {
+    let value = property.value.trim();
+
+    let result = if let Some(utc) = value.strip_suffix('Z') {
+        NaiveDateTime::parse_from_str(utc, LOCAL_FORMAT)
+            .ok()
+            .map(|dt| Utc.from_utc_datetime(&dt).with_timezone(&Local))
+    } else if let Ok(dt) = NaiveDateTime::parse_from_str(value, LOCAL_FORMAT) {
+        Local.from_local_datetime(&dt).earliest()
+    } else {
+        NaiveDate::parse_from_str(value, DATE_FORMAT)
+            .ok()
+            .and_then(|d| Local.from_local_datetime(&d.and_hms_opt(0, 0, 0)?).earliest())
+    };
+
+    result.ok_or_else(|| format!("fecha inválida en {}: '{}'", property.name, value))
+}
+
+// Duraciones como "-PT15M", "P1D", "PT1H30M" o "-P1W"
+fn parse_duration(s: &str) -> Option<Duration> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let s = s.strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+
+    for c in s.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => in_time = true,
+            _ => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (c, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+
+    if !number.is_empty() {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+// Hora de la alarma: absoluta, o relativa al inicio (o al fin con RELATED=END)
+fn alarm_time(alarm: &[Property], start: Option<DateTime<Local>>, end: Option<DateTime<Local>>) -> Option<DateTime<Local>> {
+    let trigger = alarm.iter().find(|p| p.name == "TRIGGER")?;
+
+    if trigger.param("VALUE") == Some("DATE-TIME") {
+        return parse_datetime(trigger).ok();
+    }
+
+    let base = if trigger.param("RELATED") == Some("END") { end } else { start };
+    Some(base? + parse_duration(trigger.value.trim())?)
+}
+
+fn parse_task(component: &Component) -> Result<Task, String> {
+    let title = component.get("SUMMARY")
+        .map(|p| unescape_text(&p.value))
+        .filter(|t| !t.trim().is_empty())
+        .ok_or("sin SUMMARY")?;
+    let description = component.get("DESCRIPTION").map(|p| unescape_text(&p.value));
+    let mut task = Task::new(0, title, description);
+
+    // Los UID de otras aplicaciones no siempre son UUIDs; en ese caso se genera uno
+    if let Some(uuid) = component.get("UID").and_then(|p| Uuid::parse_str(p.value.trim()).ok()) {
+        task.uuid = uuid;
+    }
+
+    for property in component.properties.iter().filter(|p| p.name == "CATEGORIES") {
+        for tag in split_list(&property.value) {
+            task.add_tag(tag);
+        }
+    }
+
+    if let Some(project) = component.get("X-RUSTASK-PROJECT") {
+        task.project = Some(unescape_text(&project.value));
+    }
+    if let Some(priority) = component.get("PRIORITY") {
+        task.priority = priority.value.trim().parse().ok().and_then(priority_from_number);
+    }
+    if let Some(created) = component.get("CREATED") {
+        task.created_at = parse_datetime(created)?.with_timezone(&Utc);
+    }
+
+    let start = component.get("DTSTART").map(parse_datetime).transpose()?;
+    // En un VEVENT el fin es DTEND; en un VTODO, DUE
+    let end_property = if component.kind == "VEVENT" { "DTEND" } else { "DUE" };
+    let end = component.get(end_property).map(parse_datetime).transpose()?;
+
+    if component.kind == "VTODO" {
+        task.due = end;
+    }
+    task.scheduled_for = component.alarms.iter()
+        .find_map(|alarm| alarm_time(alarm, start, end))
+        .or(start);
+    // Un calendario real trae muchos eventos pasados: quedan como ya avisados para
+    // que el scheduler no envíe un recordatorio y un aviso de vencida por cada uno
+    if task.scheduled_for.is_some_and(|scheduled| scheduled <= Local::now()) {
+        task.reminder_sent = true;
+        task.overdue_notified = true;
+    }
+
+    let completed = component.get("STATUS").is_some_and(|s| s.value.eq_ignore_ascii_case("COMPLETED"))
+        || component.get("COMPLETED").is_some();
+    if completed {
+        task.status = TaskStatus::Completed;
+        task.completed_at = match component.get("COMPLETED") {
+            Some(property) => Some(parse_datetime(property)?.with_timezone(&Utc)),
+            None => Some(Utc::now()),
+        };
+    }
+
+    Ok(task)
+}
+
+// Lista separada por comas, respetando "\,"
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+
+    for c in value.chars() {
+        match c {
+            ',' if !escaped => items.push(std::mem::take(&mut current)),
+            '\\' if !escaped => {
+                escaped = true;
+                current.push(c);
+                continue;
+            }
+            _ => current.push(c),
+        }
+        escaped = false;
+    }
+    items.push(current);
+
+    items.iter()
+        .map(|item| unescape_text(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+pub fn import(content: &str, existing: &[Task]) -> Result<ImportPlan, String> {
+    let components = parse_components(content)?;
+
+    let mut tasks = Vec::new();
+    let mut errors = Vec::new();
+    for (i, component) in components.iter().enumerate() {
+        match parse_task(component) {
+            Ok(task) => tasks.push(task),
+            Err(e) => errors.push(format!("{} {}: {}", component.kind, i + 1, e)),
+        }
+    }
+
+    // La fecha de creación solo sirve para detectar duplicados si todas la traen
+    let with_created_at = components.iter().all(|c| c.get("CREATED").is_some());
+    Ok(ImportPlan::build(existing, tasks, errors, with_created_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::parse_iso_datetime;
+
+    fn local(s: &str) -> DateTime<Local> {
+        parse_iso_datetime(s).unwrap()
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let mut task = Task::new_with_tags(1, "Reunión, con; el equipo".to_string(),
+                                           Some("Traer el informe\ny las cifras".to_string()),
+                                           vec!["trabajo".to_string(), "a,b".to_string()]);
+        task.priority = Some(Priority::High);
+        task.project = Some("ventas".to_string());
+        task.schedule_for(local("2025-06-04T10:30"));
+        task.due = Some(local("2025-06-05T18:00"));
+        let unscheduled = Task::new(2, "Sin fecha".to_string(), None);
+
+        let mut output = Vec::new();
+        export(&[task.clone(), unscheduled], &mut output).unwrap();
+        let ics = String::from_utf8(output).unwrap();
+
+        assert!(ics.lines().all(|l| l.len() <= MAX_LINE + 1));
+        assert!(ics.contains("BEGIN:VALARM"));
+        assert!(!ics.contains("Sin fecha"));
+
+        let plan = import(&ics, &[]).unwrap();
+        assert!(plan.errors.is_empty());
+        assert_eq!(plan.new_tasks.len(), 1);
+        let imported = &plan.new_tasks[0];
+        assert_eq!(imported.title, task.title);
+        assert_eq!(imported.description, task.description);
+        assert_eq!(imported.tags, task.tags);
+        assert_eq!(imported.priority, task.priority);
+        assert_eq!(imported.project, task.project);
+        assert_eq!(imported.scheduled_for, task.scheduled_for);
+        assert_eq!(imported.due, task.due);
+        assert_eq!(imported.uuid, task.uuid);
+
+        // Volver a importar sobre la misma tarea: duplicado
+        assert_eq!(import(&ics, &[task]).unwrap().duplicates.len(), 1);
+    }
+
+    #[test]
+    fn test_import_from_other_calendar_apps() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VTODO\r\n\
+                   SUMMARY:Pagar la factura de la l\r\n uz\r\n\
+                   DUE;TZID=Europe/Madrid:20250610T120000\r\n\
+                   STATUS:NEEDS-ACTION\r\n\
+                   BEGIN:VALARM\r\n\
+                   TRIGGER;RELATED=END:-PT1H30M\r\n\
+                   END:VALARM\r\n\
+                   END:VTODO\r\n\
+                   BEGIN:VEVENT\r\n\
+                   SUMMARY:Dentista\r\n\
+                   DTSTART;VALUE=DATE:20250612\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VTODO\r\n\
+                   DESCRIPTION:Sin título\r\n\
+                   END:VTODO\r\n\
+                   END:VCALENDAR\r\n";
+
+        let plan = import(ics, &[]).unwrap();
+        assert_eq!(plan.new_tasks.len(), 2);
+        assert_eq!(plan.errors, vec!["VTODO 3: sin SUMMARY"]);
+
+        let bill = &plan.new_tasks[0];
+        assert_eq!(bill.title, "Pagar la factura de la luz");
+        assert_eq!(bill.due, Some(local("2025-06-10T12:00")));
+        assert_eq!(bill.scheduled_for, Some(local("2025-06-10T10:30")));
+
+        let dentist = &plan.new_tasks[1];
+        assert_eq!(dentist.scheduled_for, Some(local("2025-06-12")));
+        assert!(dentist.due.is_none());
+
+        assert!(import("no es un calendario", &[]).is_err());
+    }
+
+    #[test]
+    fn test_past_items_do_not_fire_reminders() {
+        let future = (Local::now() + Duration::days(2)).format("%Y%m%dT%H%M%S");
+        let ics = format!("BEGIN:VCALENDAR\r\n\
+                           BEGIN:VEVENT\r\nSUMMARY:Reunión pasada\r\nDTSTART:20200115T090000\r\nEND:VEVENT\r\n\
+                           BEGIN:VEVENT\r\nSUMMARY:Reunión próxima\r\nDTSTART:{}\r\nEND:VEVENT\r\n\
+                           END:VCALENDAR\r\n", future);
+
+        let plan = import(&ics, &[]).unwrap();
+        let (past, upcoming) = (&plan.new_tasks[0], &plan.new_tasks[1]);
+        assert!(past.reminder_sent && past.overdue_notified);
+        assert!(!past.is_due() && !past.is_overdue(Duration::minutes(60)));
+        assert!(!upcoming.reminder_sent && !upcoming.overdue_notified);
+    }
+}
@@ -0,0 +1,123 @@
+// Importación y exportación de tareas en otros formatos
+pub mod csv;
+pub mod ical;
+pub mod report;
+pub mod taskwarrior;
+pub mod todotxt;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::ValueEnum;
+use crate::task::{Priority, Task, TaskStatus};
+
+// Tareas leídas de un archivo, separadas en nuevas y duplicadas
+#[derive(Debug, Default)]
+pub struct ImportPlan {
+    pub new_tasks: Vec<Task>,
+    pub duplicates: Vec<Task>,
+    // Filas/entradas que no se pudieron interpretar
+    pub errors: Vec<String>,
+    // Entradas que se ignoran a propósito (ej: tareas borradas en Taskwarrior)
+    pub skipped: Vec<String>,
+}
+
+impl ImportPlan {
+    // Clasifica las tareas leídas comparándolas con las existentes y entre sí.
+    // `with_created_at`: el archivo trae la fecha de creación de cada tarea.
+    pub fn build(existing: &[Task], parsed: Vec<Task>, errors: Vec<String>, with_created_at: bool) -> Self {
+        let mut plan = ImportPlan { errors, ..ImportPlan::default() };
+
+        for task in parsed {
+            let duplicated = existing.iter().any(|t| is_duplicate(t, &task, with_created_at))
+                || plan.new_tasks.iter().any(|t| is_duplicate(t, &task, with_created_at));
+
+            if duplicated {
+                plan.duplicates.push(task);
+            } else {
+                plan.new_tasks.push(task);
+            }
+        }
+
+        plan
+    }
+}
+
+// Dos tareas son la misma si tienen el mismo UUID, o si coinciden título y descripción
+// y además la fecha de creación cuando el archivo la trae (los de rustask siempre la traen)
+pub fn is_duplicate(existing: &Task, candidate: &Task, compare_created_at: bool) -> bool {
+    if existing.uuid == candidate.uuid {
+        return true;
+    }
+
+    existing.title.trim().eq_ignore_ascii_case(candidate.title.trim())
+        && existing.description == candidate.description
+        && (!compare_created_at || existing.created_at.timestamp() == candidate.created_at.timestamp())
+}
+
+// Fechas ISO 8601: "2025-06-04T10:00:00+02:00", "2025-06-04 10:00" o "2025-06-04"
+pub fn parse_iso_datetime(s: &str) -> Result<DateTime<Local>, String> {
+    let s = s.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Local));
+    }
+
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+            return local_datetime(dt);
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return local_datetime(date.and_hms_opt(0, 0, 0).unwrap());
+    }
+
+    Err(format!("fecha inválida '{}' (se espera ISO 8601, ej: 2025-06-04T10:00:00)", s))
+}
+
+pub fn parse_iso_datetime_utc(s: &str) -> Result<DateTime<Utc>, String> {
+    parse_iso_datetime(s).map(|dt| dt.with_timezone(&Utc))
+}
+
+fn local_datetime(dt: NaiveDateTime) -> Result<DateTime<Local>, String> {
+    Local.from_local_datetime(&dt)
+        .earliest()
+        .ok_or_else(|| format!("la hora {} no existe en la zona horaria local", dt))
+}
+
+pub fn status_name(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "pending",
+        TaskStatus::Completed => "completed",
+    }
+}
+
+pub fn parse_status(s: &str) -> Result<TaskStatus, String> {
+    match s.trim().to_lowercase().as_str() {
+        "" | "pending" | "pendiente" => Ok(TaskStatus::Pending),
+        "completed" | "completada" | "done" => Ok(TaskStatus::Completed),
+        other => Err(format!("estado inválido '{}' (use pending o completed)", other)),
+    }
+}
+
+pub fn priority_name(priority: Priority) -> &'static str {
+    match priority {
+        Priority::High => "high",
+        Priority::Medium => "medium",
+        Priority::Low => "low",
+    }
+}
+
+// "high", "h", o una letra de todo.txt ("A")
+pub fn parse_priority(s: &str) -> Result<Priority, String> {
+    let s = s.trim();
+    if let Ok(priority) = Priority::from_str(s, true) {
+        return Ok(priority);
+    }
+
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) => Priority::from_letter(letter),
+        _ => None,
+    }
+    .ok_or_else(|| format!("prioridad inválida '{}' (use high, medium o low)", s))
+}
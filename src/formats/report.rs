@@ -0,0 +1,244 @@
+use std::io::Write;
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use crate::task::Task;
+use crate::template;
+use super::priority_name;
+
+// Informes para pegar en un documento o un status semanal (sin emojis)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportStyle {
+    Markdown,
+    Org,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum GroupBy {
+    #[default]
+    Status,
+    // Una tarea con varios tags aparece en cada uno
+    Tag,
+}
+
+const DATE_FORMAT: &str = "%d/%m/%Y %H:%M";
+const UNTAGGED: &str = "Sin tag";
+
+// `task_template` reemplaza el bloque de cada tarea. Marcadores: {{id}}, {{uuid}}, {{title}},
+// {{description}}, {{checkbox}} ("[ ]"/"[x]" en Markdown, "TODO"/"DONE" en Org),
+// {{status}}, {{tags}}, {{project}}, {{priority}}, {{scheduled}}, {{due}},
+// {{created}} y {{completed}}. Los campos vacíos se reemplazan por "".
+pub fn export<W: Write>(tasks: &[Task], style: ReportStyle, group_by: GroupBy, task_template: Option<&str>, mut writer: W) -> Result<(), String> {
+    let mut output = match style {
+        ReportStyle::Markdown => format!("# Tareas ({})\n", Local::now().format("%d/%m/%Y")),
+        ReportStyle::Org => format!("#+TITLE: Tareas ({})\n", Local::now().format("%d/%m/%Y")),
+    };
+
+    for (group, group_tasks) in group_tasks(tasks, group_by) {
+        match style {
+            ReportStyle::Markdown => output.push_str(&format!("\n## {} ({})\n\n", group, group_tasks.len())),
+            ReportStyle::Org => output.push_str(&format!("\n* {} [{}]\n", group, group_tasks.len())),
+        }
+
+        for task in group_tasks {
+            let block = match task_template {
+                Some(task_template) => render_template(task_template, task, style),
+                None => match style {
+                    ReportStyle::Markdown => markdown_task(task),
+                    ReportStyle::Org => org_task(task),
+                },
+            };
+            output.push_str(&block);
+            if !block.ends_with('\n') {
+                output.push('\n');
+            }
+        }
+    }
+
+    writer.write_all(output.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn group_tasks(tasks: &[Task], group_by: GroupBy) -> Vec<(String, Vec<&Task>)> {
+    match group_by {
+        GroupBy::Status => {
+            let (completed, pending): (Vec<&Task>, Vec<&Task>) = tasks.iter().partition(|t| t.is_completed());
+            [("Pendientes", pending), ("Completadas", completed)]
+                .into_iter()
+                .filter(|(_, tasks)| !tasks.is_empty())
+                .map(|(name, tasks)| (name.to_string(), tasks))
+                .collect()
+        }
+        GroupBy::Tag => {
+            let mut tags: Vec<&str> = tasks.iter().flat_map(|t| t.tags.iter().map(|tag| tag.as_str())).collect();
+            tags.sort();
+            tags.dedup();
+
+            let mut groups: Vec<(String, Vec<&Task>)> = tags.into_iter()
+                .map(|tag| (tag.to_string(), tasks.iter().filter(|t| t.has_tag(tag)).collect()))
+                .collect();
+
+            let untagged: Vec<&Task> = tasks.iter().filter(|t| t.tags.is_empty()).collect();
+            if !untagged.is_empty() {
+                groups.push((UNTAGGED.to_string(), untagged));
+            }
+            groups
+        }
+    }
+}
+
+fn markdown_task(task: &Task) -> String {
+    let checkbox = if task.is_completed() { "[x]" } else { "[ ]" };
+    let mut block = format!("- {} {}", checkbox, task.title);
+
+    if let Some(ref project) = task.project {
+        block.push_str(&format!(" ({})", project));
+    }
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|t| format!("`{}`", t)).collect();
+        block.push_str(&format!(" {}", tags.join(" ")));
+    }
+    block.push('\n');
+
+    if let Some(ref description) = task.description {
+        for line in description.lines() {
+            block.push_str(&format!("  > {}\n", line));
+        }
+    }
+    for (label, date) in task_dates(task) {
+        block.push_str(&format!("  - {}: {}\n", label, date.format(DATE_FORMAT)));
+    }
+
+    block
+}
+
+fn org_task(task: &Task) -> String {
+    let keyword = if task.is_completed() { "DONE" } else { "TODO" };
+    let mut block = format!("** {} ", keyword);
+
+    if let Some(priority) = task.priority {
+        block.push_str(&format!("[#{}] ", priority.letter()));
+    }
+    block.push_str(&task.title);
+
+    if !task.tags.is_empty() {
+        // Org no admite espacios en los tags
+        let tags: Vec<String> = task.tags.iter().map(|t| t.replace(' ', "_")).collect();
+        block.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    block.push('\n');
+
+    // Línea de planificación de Org: CLOSED, DEADLINE y SCHEDULED
+    let mut planning = Vec::new();
+    if let Some(completed_at) = task.completed_at {
+        planning.push(format!("CLOSED: [{}]", org_date(completed_at.with_timezone(&Local))));
+    }
+    if let Some(due) = task.due {
+        planning.push(format!("DEADLINE: <{}>", org_date(due)));
+    }
+    if let Some(scheduled) = task.scheduled_for {
+        planning.push(format!("SCHEDULED: <{}>", org_date(scheduled)));
+    }
+    if !planning.is_empty() {
+        block.push_str(&format!("   {}\n", planning.join(" ")));
+    }
+
+    if let Some(ref project) = task.project {
+        block.push_str(&format!("   :PROPERTIES:\n   :PROJECT: {}\n   :END:\n", project));
+    }
+    if let Some(ref description) = task.description {
+        for line in description.lines() {
+            block.push_str(&format!("   {}\n", line));
+        }
+    }
+
+    block
+}
+
+fn org_date(dt: DateTime<Local>) -> String {
+    dt.format("%Y-%m-%d %a %H:%M").to_string()
+}
+
+fn task_dates(task: &Task) -> Vec<(&'static str, DateTime<Local>)> {
+    let mut dates = Vec::new();
+    if let Some(scheduled) = task.scheduled_for {
+        dates.push(("Programada", scheduled));
+    }
+    if let Some(due) = task.due {
+        dates.push(("Fecha límite", due));
+    }
+    if let Some(completed_at) = task.completed_at {
+        dates.push(("Completada", completed_at.with_timezone(&Local)));
+    }
+    dates
+}
+
+fn render_template(task_template: &str, task: &Task, style: ReportStyle) -> String {
+    let format_date = |dt: Option<DateTime<Local>>| dt.map(|d| d.format(DATE_FORMAT).to_string()).unwrap_or_default();
+
+    template::render(task_template, |key| {
+        let value = match key {
+            "id" => task.id.to_string(),
+            "uuid" => task.uuid.to_string(),
+            "title" => task.title.clone(),
+            "description" => task.description.clone().unwrap_or_default(),
+            "checkbox" => match (style, task.is_completed()) {
+                (ReportStyle::Markdown, true) => "[x]",
+                (ReportStyle::Markdown, false) => "[ ]",
+                (ReportStyle::Org, true) => "DONE",
+                (ReportStyle::Org, false) => "TODO",
+            }.to_string(),
+            "status" => if task.is_completed() { "completada" } else { "pendiente" }.to_string(),
+            "tags" => task.tags.join(", "),
+            "project" => task.project.clone().unwrap_or_default(),
+            "priority" => task.priority.map(|p| priority_name(p).to_string()).unwrap_or_default(),
+            "scheduled" => format_date(task.scheduled_for),
+            "due" => format_date(task.due),
+            "created" => format_date(Some(task.created_at.with_timezone(&Local))),
+            "completed" => format_date(task.completed_at.map(|d| d.with_timezone(&Local))),
+            _ => return None,
+        };
+        Some(value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tasks() -> Vec<Task> {
+        let mut done = Task::new_with_tags(1, "Enviar informe".to_string(), Some("Versión final".to_string()),
+                                           vec!["trabajo".to_string()]);
+        done.complete();
+        let mut pending = Task::new_with_tags(2, "Llamar a Ana".to_string(), None,
+                                              vec!["trabajo".to_string(), "tel".to_string()]);
+        pending.schedule_for(crate::formats::parse_iso_datetime("2025-06-04T10:30").unwrap());
+        let untagged = Task::new(3, "Regar plantas".to_string(), None);
+        vec![done, pending, untagged]
+    }
+
+    fn report(style: ReportStyle, group_by: GroupBy, task_template: Option<&str>) -> String {
+        let mut output = Vec::new();
+        export(&sample_tasks(), style, group_by, task_template, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_markdown_grouped_by_status() {
+        let markdown = report(ReportStyle::Markdown, GroupBy::Status, None);
+
+        assert!(markdown.contains("## Pendientes (2)\n\n- [ ] Llamar a Ana `trabajo` `tel`\n  - Programada: 04/06/2025 10:30\n"));
+        assert!(markdown.contains("## Completadas (1)\n\n- [x] Enviar informe `trabajo`\n  > Versión final\n  - Completada:"));
+        assert!(markdown.find("Pendientes") < markdown.find("Completadas"));
+    }
+
+    #[test]
+    fn test_org_grouped_by_tag_with_template() {
+        let org = report(ReportStyle::Org, GroupBy::Tag, None);
+        assert!(org.contains("* tel [1]\n** TODO Llamar a Ana :trabajo:tel:\n   SCHEDULED: <2025-06-04 Wed 10:30>\n"));
+        assert!(org.contains("* trabajo [2]"));
+        assert!(org.contains("* Sin tag [1]\n** TODO Regar plantas\n"));
+
+        let custom = report(ReportStyle::Markdown, GroupBy::Status, Some("{{checkbox}} #{{id}} {{title}} {{scheduled}}"));
+        assert!(custom.contains("[ ] #2 Llamar a Ana 04/06/2025 10:30\n"));
+        assert!(custom.contains("[x] #1 Enviar informe \n"));
+    }
+}
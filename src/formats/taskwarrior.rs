@@ -0,0 +1,336 @@
+use std::io::Write;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+use crate::task::{Priority, Task, TaskStatus};
+use super::{parse_iso_datetime, ImportPlan};
+
+// JSON de Taskwarrior ('task export' / 'task import'):
+//   description   título
+//   annotations   descripción (una línea por anotación)
+//   entry/end     created_at/completed_at
+//   due           due
+//   scheduled     scheduled_for
+//   wait          snoozed_until (si ya pasó queda en extras: no debe disparar
+//                 un recordatorio al importar)
+//   priority      H/M/L
+//   uuid          uuid
+// El resto de atributos (UDAs, depends, recur...) se guardan en
+// Task::extras y se vuelven a escribir tal cual al exportar.
+
+const DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+// Atributos que Taskwarrior calcula y no tiene sentido conservar. 'modified'
+// dejaría de ser cierto con el primer cambio en rustask; 'task import' lo pone.
+const COMPUTED: [&str; 3] = ["id", "urgency", "modified"];
+
+pub fn export<W: Write>(tasks: &[Task], mut writer: W) -> Result<(), String> {
+    // Igual que 'task export': un array con una tarea por línea
+    writeln!(writer, "[").map_err(|e| e.to_string())?;
+    for (i, task) in tasks.iter().enumerate() {
+        let json = serde_json::to_string(&to_taskwarrior(task)).map_err(|e| e.to_string())?;
+        let separator = if i + 1 < tasks.len() { "," } else { "" };
+        writeln!(writer, "{}{}", json, separator).map_err(|e| e.to_string())?;
+    }
+    writeln!(writer, "]").map_err(|e| e.to_string())
+}
+
+fn to_taskwarrior(task: &Task) -> Map<String, Value> {
+    let mut object: Map<String, Value> = task.extras.clone().into_iter().collect();
+
+    let status = match (&task.status, object.get("status").and_then(|s| s.as_str())) {
+        // "waiting", "recurring" y "deleted" solo se conservan si siguen siendo coherentes
+        (TaskStatus::Pending, Some(original @ ("waiting" | "recurring"))) => original.to_string(),
+        (TaskStatus::Completed, Some("deleted")) => "deleted".to_string(),
+        (TaskStatus::Pending, _) => "pending".to_string(),
+        (TaskStatus::Completed, _) => "completed".to_string(),
+    };
+    object.insert("status".to_string(), json!(status));
+    object.insert("uuid".to_string(), json!(task.uuid));
+    object.insert("description".to_string(), json!(task.title));
+    object.insert("entry".to_string(), json!(format_date(task.created_at)));
+
+    set_optional(&mut object, "end", task.completed_at.map(format_date));
+    set_optional(&mut object, "due", task.due.map(format_local));
+    set_optional(&mut object, "scheduled", task.scheduled_for.map(format_local));
+    // Sin snooze se conserva el 'wait' ya pasado que vino en extras
+    if let Some(snoozed) = task.snoozed_until {
+        object.insert("wait".to_string(), json!(format_local(snoozed)));
+    }
+    set_optional(&mut object, "project", task.project.clone());
+    set_optional(&mut object, "priority", task.priority.map(|p| priority_code(p).to_string()));
+
+    if task.tags.is_empty() {
+        object.remove("tags");
+    } else {
+        object.insert("tags".to_string(), json!(task.tags));
+    }
+
+    let annotations = annotations_for(task, object.get("annotations"));
+    set_optional(&mut object, "annotations", annotations);
+
+    object
+}
+
+fn set_optional<T: Into<Value>>(object: &mut Map<String, Value>, key: &str, value: Option<T>) {
+    match value {
+        Some(value) => object.insert(key.to_string(), value.into()),
+        None => object.remove(key),
+    };
+}
+
+// Las anotaciones originales se reutilizan mientras la descripción no cambie,
+// para conservar sus fechas; si cambió, cada línea es una anotación nueva
+fn annotations_for(task: &Task, original: Option<&Value>) -> Option<Value> {
+    let description = task.description.as_ref()?;
+
+    if let Some(original) = original {
+        if annotations_text(original).as_ref() == Some(description) {
+            return Some(original.clone());
+        }
+    }
+
+    let entry = format_date(task.created_at);
+    let annotations: Vec<Value> = description.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| json!({ "entry": entry, "description": line }))
+        .collect();
+    Some(Value::Array(annotations))
+}
+
+fn annotations_text(annotations: &Value) -> Option<String> {
+    let lines: Vec<&str> = annotations.as_array()?
+        .iter()
+        .filter_map(|a| a.get("description").and_then(|d| d.as_str()))
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+fn priority_code(priority: Priority) -> &'static str {
+    match priority {
+        Priority::High => "H",
+        Priority::Medium => "M",
+        Priority::Low => "L",
+    }
+}
+
+fn format_date(dt: DateTime<Utc>) -> String {
+    dt.format(DATE_FORMAT).to_string()
+}
+
+fn format_local(dt: DateTime<Local>) -> String {
+    format_date(dt.with_timezone(&Utc))
+}
+
+// "20250604T100000Z"; también se aceptan fechas ISO 8601
+fn parse_date(value: &Value, key: &str) -> Result<DateTime<Local>, String> {
+    let s = value.as_str().ok_or_else(|| format!("'{}' no es una fecha", key))?;
+
+    match NaiveDateTime::parse_from_str(s, DATE_FORMAT) {
+        Ok(dt) => Ok(Utc.from_utc_datetime(&dt).with_timezone(&Local)),
+        Err(_) => parse_iso_datetime(s),
+    }
+}
+
+fn from_taskwarrior(mut object: Map<String, Value>) -> Result<Task, String> {
+    for key in COMPUTED {
+        object.remove(key);
+    }
+
+    let title = object.remove("description")
+        .and_then(|d| d.as_str().map(|s| s.to_string()))
+        .filter(|d| !d.trim().is_empty())
+        .ok_or("sin 'description'")?;
+    let mut task = Task::new(0, title, None);
+
+    if let Some(uuid) = object.remove("uuid") {
+        task.uuid = uuid.as_str()
+            .and_then(|u| Uuid::parse_str(u).ok())
+            .ok_or_else(|| format!("uuid inválido {}", uuid))?;
+    }
+
+    if let Some(tags) = object.remove("tags") {
+        for tag in tags.as_array().ok_or("'tags' no es una lista")?.iter().filter_map(|t| t.as_str()) {
+            task.add_tag(tag.to_string());
+        }
+    }
+
+    if let Some(status) = object.get("status").and_then(|s| s.as_str()) {
+        task.status = match status {
+            "pending" | "waiting" => TaskStatus::Pending,
+            "completed" => TaskStatus::Completed,
+            other => return Err(format!("estado desconocido '{}'", other)),
+        };
+        // Solo se guarda en extras si no es uno de los de rustask
+        if status == "pending" || status == "completed" {
+            object.remove("status");
+        }
+    }
+
+    if let Some(entry) = object.remove("entry") {
+        task.created_at = parse_date(&entry, "entry")?.with_timezone(&Utc);
+    }
+    if let Some(end) = object.remove("end") {
+        task.completed_at = Some(parse_date(&end, "end")?.with_timezone(&Utc));
+    }
+    if let Some(due) = object.remove("due") {
+        task.due = Some(parse_date(&due, "due")?);
+    }
+    if let Some(scheduled) = object.remove("scheduled") {
+        task.scheduled_for = Some(parse_date(&scheduled, "scheduled")?);
+    }
+    if let Some(wait) = object.get("wait") {
+        let wait = parse_date(wait, "wait")?;
+        if wait > Local::now() {
+            task.snoozed_until = Some(wait);
+            object.remove("wait");
+        }
+    }
+    if let Some(project) = object.remove("project") {
+        task.project = project.as_str().map(|p| p.to_string());
+    }
+    if let Some(priority) = object.remove("priority") {
+        task.priority = match priority.as_str() {
+            Some("H") => Some(Priority::High),
+            Some("M") => Some(Priority::Medium),
+            Some("L") => Some(Priority::Low),
+            _ => None,
+        };
+    }
+
+    // Las anotaciones quedan también en extras para conservar sus fechas
+    task.description = object.get("annotations").and_then(annotations_text);
+
+    if task.is_completed() && task.completed_at.is_none() {
+        task.completed_at = Some(Utc::now());
+    }
+
+    task.extras = object.into_iter().collect();
+    Ok(task)
+}
+
+// Acepta el array de 'task export' o un objeto JSON por línea (versiones antiguas)
+pub fn import(content: &str, existing: &[Task]) -> Result<ImportPlan, String> {
+    let objects: Vec<Value> = match serde_json::from_str::<Vec<Value>>(content) {
+        Ok(objects) => objects,
+        Err(array_error) => {
+            let lines: Result<Vec<Value>, _> = content.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect();
+            lines.map_err(|_| format!("JSON inválido: {}", array_error))?
+        }
+    };
+
+    let mut tasks = Vec::new();
+    let mut errors = Vec::new();
+    let mut skipped = Vec::new();
+    for (i, object) in objects.into_iter().enumerate() {
+        // Las borradas no son tareas vivas, y las plantillas de recurrencia ya
+        // vienen exportadas como sus instancias: importarlas las duplicaría
+        let reason = match object.get("status").and_then(|s| s.as_str()) {
+            Some("deleted") => Some("borrada"),
+            Some("recurring") => Some("plantilla de recurrencia"),
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            let title = object.get("description").and_then(|d| d.as_str()).unwrap_or("?");
+            skipped.push(format!("tarea {}: {} ({})", i + 1, title, reason));
+            continue;
+        }
+
+        let result = match object {
+            Value::Object(object) => from_taskwarrior(object),
+            _ => Err("no es un objeto".to_string()),
+        };
+        match result {
+            Ok(task) => tasks.push(task),
+            Err(e) => errors.push(format!("tarea {}: {}", i + 1, e)),
+        }
+    }
+
+    let mut plan = ImportPlan::build(existing, tasks, errors, true);
+    plan.skipped = skipped;
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"[
+{"id":3,"description":"Revisar PR","entry":"20250601T080000Z","modified":"20250602T090000Z","project":"rustask","priority":"H","status":"pending","tags":["code","review"],"due":"20250610T170000Z","wait":"20250605T080000Z","uuid":"4f6c2a1e-8d7b-4c1e-9f3a-2b5d6e7f8a9b","annotations":[{"entry":"20250601T081500Z","description":"ver los tests"},{"entry":"20250602T090000Z","description":"y la doc"}],"estimate":"2h","urgency":12.3},
+{"id":0,"description":"Vieja","entry":"20250501T080000Z","end":"20250502T080000Z","status":"deleted","uuid":"0b1c2d3e-0000-4000-8000-000000000000"}
+]"#;
+
+    #[test]
+    fn test_import_maps_fields_and_keeps_extras() {
+        let plan = import(EXPORT, &[]).unwrap();
+        assert!(plan.errors.is_empty());
+        assert_eq!(plan.new_tasks.len(), 1);
+
+        let task = &plan.new_tasks[0];
+        assert_eq!(task.title, "Revisar PR");
+        assert_eq!(task.description.as_deref(), Some("ver los tests\ny la doc"));
+        assert_eq!(task.project.as_deref(), Some("rustask"));
+        assert_eq!(task.priority, Some(Priority::High));
+        assert_eq!(task.tags, vec!["code", "review"]);
+        // El 'wait' ya pasó: no es un snooze pendiente
+        assert_eq!(task.snoozed_until, None);
+        assert_eq!(task.extras["wait"], "20250605T080000Z");
+        assert_eq!(task.uuid.to_string(), "4f6c2a1e-8d7b-4c1e-9f3a-2b5d6e7f8a9b");
+        assert_eq!(task.extras["estimate"], "2h");
+        assert!(COMPUTED.iter().all(|key| !task.extras.contains_key(*key)));
+
+        let waiting = r#"{"description":"Más tarde","status":"waiting","wait":"20990101T080000Z","uuid":"1b1c2d3e-0000-4000-8000-000000000000"}"#;
+        let task = &import(waiting, &[]).unwrap().new_tasks[0];
+        assert_eq!(task.snoozed_until.map(format_local).as_deref(), Some("20990101T080000Z"));
+        assert!(!task.extras.contains_key("wait"));
+    }
+
+    #[test]
+    fn test_skips_deleted_and_recurring_templates() {
+        let content = r#"[
+{"description":"Regar","status":"recurring","recur":"weekly","due":"20250601T080000Z","uuid":"2b1c2d3e-0000-4000-8000-000000000000"},
+{"description":"Regar","status":"pending","recur":"weekly","due":"20250601T080000Z","parent":"2b1c2d3e-0000-4000-8000-000000000000","uuid":"3b1c2d3e-0000-4000-8000-000000000000"}
+]"#;
+        let plan = import(content, &[]).unwrap();
+        assert_eq!(plan.new_tasks.len(), 1);
+        assert_eq!(plan.new_tasks[0].uuid.to_string(), "3b1c2d3e-0000-4000-8000-000000000000");
+        assert_eq!(plan.skipped, vec!["tarea 1: Regar (plantilla de recurrencia)"]);
+
+        let plan = import(EXPORT, &[]).unwrap();
+        assert_eq!(plan.skipped, vec!["tarea 2: Vieja (borrada)"]);
+        assert!(plan.new_tasks.iter().all(|t| t.title != "Vieja"));
+    }
+
+    #[test]
+    fn test_roundtrip_is_lossless() {
+        let tasks = import(EXPORT, &[]).unwrap().new_tasks;
+
+        let mut output = Vec::new();
+        export(&tasks, &mut output).unwrap();
+        let exported: Vec<Value> = serde_json::from_slice(&output).unwrap();
+        let original: Vec<Value> = serde_json::from_str(EXPORT).unwrap();
+
+        for (mut original, exported) in original.into_iter().zip(exported) {
+            let original = original.as_object_mut().unwrap();
+            for key in COMPUTED {
+                original.remove(key);
+            }
+            assert_eq!(&Value::Object(original.clone()), &exported);
+        }
+
+        // Si la descripción cambia, las anotaciones se regeneran
+        let mut task = tasks[0].clone();
+        task.description = Some("nueva nota".to_string());
+        let annotations = &to_taskwarrior(&task)["annotations"];
+        assert_eq!(annotations.as_array().unwrap().len(), 1);
+        assert_eq!(annotations[0]["description"], "nueva nota");
+    }
+}
@@ -0,0 +1,432 @@
+use std::io::Write;
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, Utc};
+use uuid::Uuid;
+use crate::task::{Priority, Task, TaskStatus, TimeEntry};
+use super::{parse_iso_datetime, ImportPlan};
+
+// Formato todo.txt (https://github.com/todotxt/todo.txt):
+//   x 2025-06-05 2025-06-01 Llamar a Ana +trabajo @telefono due:2025-06-10 id:3
+//
+// Correspondencia con Task:
+//   (A)/(B)/(C)        prioridad alta/media/baja (de D en adelante, baja)
+//   +proyecto          project (los siguientes +proyecto quedan como tags "+proyecto")
+//   @contexto          tags
+//   due:               fecha límite
+//   x fecha            completada y completed_at
+//   fecha de creación  created_at
+// Extensiones propias para no perder el estado de rustask:
+//   id:, uuid:, sched: (scheduled_for), snooze: (snoozed_until), snoozes: (snooze_count),
+//   reminded:1 (reminder_sent), overdue:1 (overdue_notified),
+//   time:inicio/fin,... (time_entries, en UTC; sin fin si sigue corriendo),
+//   pomos:fin,... (pomodoros, en UTC), est:minutos (estimate_minutes),
+//   reviewed: (last_reviewed, en UTC), desc: (description) y x: (extras en JSON).
+//   En desc: y x: los espacios y el % se escriben como %XX para que el valor
+//   siga siendo una sola palabra.
+//
+// Como almacenamiento principal, la primera línea guarda el próximo ID corto
+// ("# rustask next_id:8") para no reutilizar los de tareas eliminadas. Al
+// importar se ignora.
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+const NEXT_ID_HEADER: &str = "# rustask next_id:";
+
+pub fn format_next_id_header(next_id: u64) -> String {
+    format!("{}{}", NEXT_ID_HEADER, next_id)
+}
+
+// El next_id si `line` es la cabecera que escribe rustask
+pub fn parse_next_id_header(line: &str) -> Option<u64> {
+    line.trim().strip_prefix(NEXT_ID_HEADER)?.parse().ok()
+}
+
+pub fn export<W: Write>(tasks: &[Task], mut writer: W) -> Result<(), String> {
+    for task in tasks {
+        writeln!(writer, "{}", format_line(task)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+pub fn format_line(task: &Task) -> String {
+    let mut parts: Vec<String> = Vec::new();
+
+    if task.is_completed() {
+        parts.push("x".to_string());
+        if let Some(completed_at) = task.completed_at {
+            parts.push(format_date(completed_at));
+        }
+    } else if let Some(priority) = task.priority {
+        parts.push(format!("({})", priority.letter()));
+    }
+
+    parts.push(format_date(task.created_at));
+    parts.push(task.title.clone());
+
+    if let Some(ref project) = task.project {
+        parts.push(format!("+{}", project));
+    }
+
+    for tag in &task.tags {
+        // Los tags "+algo" vienen de proyectos adicionales
+        if tag.starts_with('+') {
+            parts.push(tag.clone());
+        } else {
+            parts.push(format!("@{}", tag));
+        }
+    }
+
+    // Las tareas completadas guardan la prioridad como pri: (convención de todo.txt)
+    if task.is_completed() {
+        if let Some(priority) = task.priority {
+            parts.push(format!("pri:{}", priority.letter()));
+        }
+    }
+
+    if let Some(due) = task.due {
+        parts.push(format!("due:{}", format_local(due)));
+    }
+    if let Some(scheduled) = task.scheduled_for {
+        parts.push(format!("sched:{}", format_local(scheduled)));
+    }
+    if let Some(snoozed) = task.snoozed_until {
+        parts.push(format!("snooze:{}", format_local(snoozed)));
+    }
+    if task.snooze_count > 0 {
+        parts.push(format!("snoozes:{}", task.snooze_count));
+    }
+    if task.reminder_sent {
+        parts.push("reminded:1".to_string());
+    }
+    if task.overdue_notified {
+        parts.push("overdue:1".to_string());
+    }
+    if let Some(estimate) = task.estimate_minutes {
+        parts.push(format!("est:{}", estimate));
+    }
+    if let Some(reviewed) = task.last_reviewed {
+        parts.push(format!("reviewed:{}", format_utc(reviewed)));
+    }
+    if !task.time_entries.is_empty() {
+        let entries: Vec<String> = task.time_entries.iter()
+            .map(|e| format!("{}/{}", format_utc(e.start), e.end.map(format_utc).unwrap_or_default()))
+            .collect();
+        parts.push(format!("time:{}", entries.join(",")));
+    }
+    if !task.pomodoros.is_empty() {
+        let pomodoros: Vec<String> = task.pomodoros.iter().copied().map(format_utc).collect();
+        parts.push(format!("pomos:{}", pomodoros.join(",")));
+    }
+    if let Some(description) = task.description.as_deref().filter(|d| !d.is_empty()) {
+        parts.push(format!("desc:{}", escape(description)));
+    }
+    if !task.extras.is_empty() {
+        if let Ok(extras) = serde_json::to_string(&task.extras) {
+            parts.push(format!("x:{}", escape(&extras)));
+        }
+    }
+
+    parts.push(format!("id:{}", task.id));
+    parts.push(format!("uuid:{}", task.uuid));
+    parts.join(" ")
+}
+
+// Interpreta una línea; el ID queda en 0 si la línea no tiene id:
+pub fn parse_line(line: &str) -> Result<Task, String> {
+    let mut tokens = line.split_whitespace().peekable();
+    let mut task = Task::new(0, String::new(), None);
+
+    if tokens.peek() == Some(&"x") {
+        tokens.next();
+        task.status = TaskStatus::Completed;
+
+        // Fecha de completado y, opcionalmente, de creación
+        if let Some(date) = tokens.peek().and_then(|t| parse_date(t)) {
+            tokens.next();
+            task.completed_at = Some(date);
+        }
+    } else if let Some(priority) = tokens.peek().and_then(|t| parse_priority_token(t)) {
+        tokens.next();
+        task.priority = Some(priority);
+    }
+
+    if let Some(date) = tokens.peek().and_then(|t| parse_date(t)) {
+        tokens.next();
+        task.created_at = date;
+    }
+
+    let mut title: Vec<&str> = Vec::new();
+    for token in tokens {
+        if let Some(project) = token.strip_prefix('+').filter(|p| !p.is_empty()) {
+            if task.project.is_none() {
+                task.project = Some(project.to_string());
+            } else {
+                task.add_tag(token.to_string());
+            }
+        } else if let Some(context) = token.strip_prefix('@').filter(|c| !c.is_empty()) {
+            task.add_tag(context.to_string());
+        } else if !parse_extension(&mut task, token) {
+            title.push(token);
+        }
+    }
+
+    if title.is_empty() {
+        return Err(format!("línea sin título: '{}'", line.trim()));
+    }
+    task.title = title.join(" ");
+
+    Ok(task)
+}
+
+// Interpreta un "clave:valor" conocido; retorna false si no lo es o si el
+// valor no se entiende (ej: due:mañana), y entonces queda en el título como
+// cualquier otra palabra. Así la línea se vuelve a escribir igual.
+fn parse_extension(task: &mut Task, token: &str) -> bool {
+    let Some((key, value)) = token.split_once(':') else {
+        return false;
+    };
+    if value.is_empty() {
+        return false;
+    }
+
+    match key {
+        "due" => parse_iso_datetime(value).map(|due| task.due = Some(due)).is_ok(),
+        "sched" => parse_iso_datetime(value).map(|scheduled| task.scheduled_for = Some(scheduled)).is_ok(),
+        "snooze" => parse_iso_datetime(value).map(|snoozed| task.snoozed_until = Some(snoozed)).is_ok(),
+        "snoozes" => value.parse().map(|count| task.snooze_count = count).is_ok(),
+        "reminded" => {
+            task.reminder_sent = value == "1";
+            true
+        },
+        "overdue" => {
+            task.overdue_notified = value == "1";
+            true
+        },
+        "est" => value.parse().map(|minutes| task.estimate_minutes = Some(minutes)).is_ok(),
+        "reviewed" => parse_utc(value).map(|reviewed| task.last_reviewed = Some(reviewed)).is_some(),
+        "time" => value.split(',').map(parse_time_entry).collect::<Option<Vec<_>>>()
+            .map(|entries| task.time_entries = entries).is_some(),
+        "pomos" => value.split(',').map(parse_utc).collect::<Option<Vec<_>>>()
+            .map(|pomodoros| task.pomodoros = pomodoros).is_some(),
+        "desc" => unescape(value).map(|description| task.description = Some(description)).is_some(),
+        "x" => unescape(value).and_then(|extras| serde_json::from_str(&extras).ok())
+            .map(|extras| task.extras = extras).is_some(),
+        "id" => value.parse().map(|id| task.id = id).is_ok(),
+        "uuid" => Uuid::parse_str(value).map(|uuid| task.uuid = uuid).is_ok(),
+        "pri" => value.chars().next().and_then(Priority::from_letter).map(|priority| task.priority = Some(priority)).is_some(),
+        // Claves desconocidas (o URLs) quedan en el título para no perderlas
+        _ => false,
+    }
+}
+
+// "inicio/fin" o "inicio/" si el registro sigue abierto
+fn parse_time_entry(value: &str) -> Option<TimeEntry> {
+    let (start, end) = value.split_once('/')?;
+    let end = match end {
+        "" => None,
+        end => Some(parse_utc(end)?),
+    };
+    Some(TimeEntry { start: parse_utc(start)?, end })
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::new();
+    for c in value.chars() {
+        if c == '%' || c.is_whitespace() {
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                escaped.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn parse_priority_token(token: &str) -> Option<Priority> {
+    let letter = token.strip_prefix('(')?.strip_suffix(')')?;
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_uppercase() => Priority::from_letter(c),
+        _ => None,
+    }
+}
+
+fn parse_date(token: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(token, DATE_FORMAT).ok()?;
+    date.and_hms_opt(0, 0, 0)?
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn format_date(dt: DateTime<Utc>) -> String {
+    dt.with_timezone(&Local).format(DATE_FORMAT).to_string()
+}
+
+// Registros de tiempo, pomodoros y revisiones: con segundos y en UTC, sin
+// depender de la zona horaria
+fn format_utc(dt: DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_utc(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|dt| dt.with_timezone(&Utc))
+}
+
+// Solo fecha si es medianoche, si no fecha y hora
+fn format_local(dt: DateTime<Local>) -> String {
+    if dt.format("%H:%M").to_string() == "00:00" {
+        dt.format(DATE_FORMAT).to_string()
+    } else {
+        dt.format(DATETIME_FORMAT).to_string()
+    }
+}
+
+// Lee todas las líneas; las vacías se ignoran y las inválidas se informan
+pub fn parse(content: &str) -> (Vec<Task>, Vec<String>) {
+    let mut tasks = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() || parse_next_id_header(line).is_some() {
+            continue;
+        }
+
+        match parse_line(line) {
+            Ok(task) => tasks.push(task),
+            Err(e) => errors.push(format!("línea {}: {}", i + 1, e)),
+        }
+    }
+
+    (tasks, errors)
+}
+
+// todo.txt solo guarda la fecha de creación, así que los duplicados se
+// detectan por título y descripción
+pub fn import(content: &str, existing: &[Task]) -> ImportPlan {
+    let (tasks, errors) = parse(content);
+    ImportPlan::build(existing, tasks, errors, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_line() {
+        let task = parse_line("x 2025-06-05 2025-06-01 Llamar a Ana +trabajo +ventas @telefono due:2025-06-10 pri:A id:3").unwrap();
+
+        assert!(task.is_completed());
+        assert_eq!(task.title, "Llamar a Ana");
+        assert_eq!(task.project.as_deref(), Some("trabajo"));
+        assert_eq!(task.tags, vec!["+ventas", "telefono"]);
+        assert_eq!(task.priority, Some(Priority::High));
+        assert_eq!(task.id, 3);
+        assert_eq!(format_date(task.created_at), "2025-06-01");
+        assert_eq!(task.completed_at.map(format_date).as_deref(), Some("2025-06-05"));
+        assert_eq!(task.due.map(format_local).as_deref(), Some("2025-06-10"));
+    }
+
+    #[test]
+    fn test_parse_keeps_unknown_extensions_in_title() {
+        let task = parse_line("(B) Revisar https://example.com rec:1w").unwrap();
+
+        assert_eq!(task.priority, Some(Priority::Medium));
+        assert_eq!(task.title, "Revisar https://example.com rec:1w");
+        assert!(parse_line("(A) +solo @tags").is_err());
+
+        // Un valor que no se entiende se conserva igual que una clave desconocida,
+        // y la línea escrita se vuelve a leer sin cambios
+        let task = parse_line("(A) Comprar leche @casa due:mañana id:x").unwrap();
+        assert_eq!(task.title, "Comprar leche due:mañana id:x");
+        assert_eq!(task.due, None);
+        let line = format_line(&task);
+        assert_eq!(format_line(&parse_line(&line).unwrap()), line);
+    }
+
+    #[test]
+    fn test_format_and_parse_roundtrip() {
+        let mut task = Task::new_with_tags(7, "Pagar alquiler".to_string(), None, vec!["casa".to_string()]);
+        task.priority = Some(Priority::Low);
+        task.project = Some("finanzas".to_string());
+        task.schedule_for(parse_iso_datetime("2025-06-04T10:30").unwrap());
+
+        let line = format_line(&task);
+        let parsed = parse_line(&line).unwrap();
+
+        assert_eq!(parsed.id, 7);
+        assert_eq!(parsed.uuid, task.uuid);
+        assert_eq!(parsed.title, task.title);
+        assert_eq!(parsed.tags, task.tags);
+        assert_eq!(parsed.priority, task.priority);
+        assert_eq!(parsed.project, task.project);
+        assert_eq!(parsed.scheduled_for, task.scheduled_for);
+    }
+
+    #[test]
+    fn test_estimate_and_review_roundtrip() {
+        let mut task = Task::new(4, "Revisar PR".to_string(), None);
+        task.estimate_minutes = Some(90);
+
+        let line = format_line(&task);
+        assert!(line.contains(" est:90 "));
+        assert_eq!(parse_line(&line).unwrap().estimate_minutes, Some(90));
+        assert_eq!(parse_line("Revisar PR est:1h").unwrap().title, "Revisar PR est:1h");
+
+        task.last_reviewed = parse_utc("2025-06-04T08:00:00Z");
+        assert_eq!(parse_line(&format_line(&task)).unwrap().last_reviewed, task.last_reviewed);
+    }
+
+    #[test]
+    fn test_time_entries_and_pomodoros_roundtrip() {
+        let mut task = Task::new(2, "Informe".to_string(), None);
+        let start = parse_utc("2025-06-04T10:30:15Z").unwrap();
+        task.time_entries = vec![
+            TimeEntry { start, end: Some(start + chrono::Duration::minutes(25)) },
+            TimeEntry { start: start + chrono::Duration::hours(1), end: None },
+        ];
+
+        let line = format_line(&task);
+        assert!(line.contains("time:2025-06-04T10:30:15Z/2025-06-04T10:55:15Z,2025-06-04T11:30:15Z/ "));
+        assert_eq!(parse_line(&line).unwrap().time_entries, task.time_entries);
+        // Un registro mal escrito queda en el título
+        assert_eq!(parse_line("Informe time:ayer").unwrap().title, "Informe time:ayer");
+
+        task.pomodoros = vec![start, start + chrono::Duration::minutes(30)];
+        let parsed = parse_line(&format_line(&task)).unwrap();
+        assert_eq!(parsed.pomodoros, task.pomodoros);
+    }
+
+    #[test]
+    fn test_description_and_extras_roundtrip() {
+        let mut task = Task::new(5, "Viaje".to_string(), Some("Reservar hotel\nal 100% y pagar".to_string()));
+        task.extras.insert("recur".to_string(), serde_json::json!("weekly"));
+        task.extras.insert("depends".to_string(), serde_json::json!(["a b"]));
+
+        let line = format_line(&task);
+        assert!(line.contains(" desc:Reservar%20hotel%0Aal%20100%25%20y%20pagar "));
+        let parsed = parse_line(&line).unwrap();
+        assert_eq!(parsed.description, task.description);
+        assert_eq!(parsed.extras, task.extras);
+        // Un escape roto queda en el título
+        assert_eq!(parse_line("Viaje desc:100%").unwrap().title, "Viaje desc:100%");
+    }
+}
@@ -0,0 +1,203 @@
+use std::fs;
+use std::io;
+use chrono::{DateTime, Local};
+use serde_json::Value;
+use uuid::Uuid;
+use crate::eventlog::{self, Event, EventKind};
+use crate::task::{Priority, Task, TaskStatus, TimeEntry};
+
+// Historial de cambios (history.jsonl): los mismos eventos que escribe el
+// registro de eventos, sin snapshots. Con el almacenamiento 'eventlog' no se
+// usa, el propio registro ya es el historial.
+pub struct History {
+    path: String,
+    // Tareas como estaban la última vez que se registraron cambios
+    saved: Vec<Task>,
+    seq: u64,
+}
+
+const DATE_FORMAT: &str = "%d/%m/%Y %H:%M";
+
+impl History {
+    pub fn open(path: &str, tasks: &[Task]) -> Self {
+        // Seguir la numeración de la última línea
+        let seq = fs::read_to_string(path).ok()
+            .and_then(|data| {
+                let last = data.lines().rev().find(|l| !l.trim().is_empty())?;
+                serde_json::from_str::<Event>(last).ok()
+            })
+            .map(|event| event.seq)
+            .unwrap_or(0);
+
+        Self { path: path.to_string(), saved: tasks.to_vec(), seq }
+    }
+
+    // Agrega los cambios desde la última llamada (incluidos los hechos fuera de rustask)
+    pub fn record(&mut self, tasks: &[Task]) -> Result<(), io::Error> {
+        let events = eventlog::diff(&self.saved, tasks);
+        if !events.is_empty() {
+            eventlog::write_events(&self.path, &mut self.seq, events)?;
+        }
+        self.saved = tasks.to_vec();
+        Ok(())
+    }
+
+    // Toma `tasks` como punto de partida sin registrar nada (ej: al cambiar de
+    // archivo o restaurar una copia, que no son cambios en las tareas)
+    pub fn rebaseline(&mut self, tasks: &[Task]) {
+        self.saved = tasks.to_vec();
+    }
+}
+
+// Eventos de un historial o de un registro de eventos, sin las snapshots
+pub fn read(path: &str) -> Result<Vec<Event>, io::Error> {
+    let events = eventlog::read_events(path)?;
+    Ok(events.into_iter().filter(|e| !matches!(e.kind, EventKind::Snapshot { .. })).collect())
+}
+
+pub fn task_id(event: &Event) -> Option<u64> {
+    match &event.kind {
+        EventKind::Added { task } | EventKind::Deleted { task } => Some(task.id),
+        EventKind::Updated { id, .. } => Some(*id),
+        EventKind::Snapshot { .. } => None,
+    }
+}
+
+// Los IDs cortos se reutilizan (todo.txt), así que las tareas se identifican por uuid
+pub fn task_uuid(event: &Event) -> Option<Uuid> {
+    match &event.kind {
+        EventKind::Added { task } | EventKind::Deleted { task } => Some(task.uuid),
+        EventKind::Updated { uuid, .. } => *uuid,
+        EventKind::Snapshot { .. } => None,
+    }
+}
+
+// Si el evento es de `task`. Los 'updated' antiguos no tienen uuid y se asignan por ID.
+pub fn belongs_to(event: &Event, task: &Task) -> bool {
+    match task_uuid(event) {
+        Some(uuid) => uuid == task.uuid,
+        None => task_id(event) == Some(task.id),
+    }
+}
+
+// Una línea por cambio, para mostrar al usuario
+pub fn describe(event: &Event) -> Vec<String> {
+    match &event.kind {
+        EventKind::Added { .. } => vec!["➕ Creada".to_string()],
+        EventKind::Deleted { .. } => vec!["🗑️ Eliminada".to_string()],
+        EventKind::Updated { changes, .. } => changes.iter()
+            .map(|(field, change)| format!("✏️ {}: {} → {}",
+                                           field_label(field), format_value(field, &change.before), format_value(field, &change.after)))
+            .collect(),
+        EventKind::Snapshot { .. } => Vec::new(),
+    }
+}
+
+fn field_label(field: &str) -> &str {
+    match field {
+        "title" => "Título",
+        "description" => "Descripción",
+        "tags" => "Tags",
+        "status" => "Estado",
+        "completed_at" => "Completada",
+        "scheduled_for" => "Programada",
+        "snoozed_until" => "Pospuesta hasta",
+        "snooze_count" => "Veces pospuesta",
+        "reminder_sent" => "Recordatorio enviado",
+        "overdue_notified" => "Aviso de vencida",
+        "priority" => "Prioridad",
+        "project" => "Proyecto",
+        "due" => "Fecha límite",
+        "extras" => "Atributos importados",
+        "time_entries" => "Tiempo registrado",
+        "pomodoros" => "Pomodoros",
+        "estimate_minutes" => "Estimación (min)",
+        "last_reviewed" => "Última revisión",
+        other => other,
+    }
+}
+
+fn format_value(field: &str, value: &Value) -> String {
+    match field {
+        "status" => if let Ok(status) = serde_json::from_value::<TaskStatus>(value.clone()) {
+            return match status {
+                TaskStatus::Pending => "pendiente",
+                TaskStatus::Completed => "completada",
+            }.to_string();
+        },
+        "priority" => if let Ok(Some(priority)) = serde_json::from_value::<Option<Priority>>(value.clone()) {
+            return priority.name().to_string();
+        },
+        "pomodoros" => if let Value::Array(pomodoros) = value {
+            return pomodoros.len().to_string();
+        },
+        "time_entries" => if let Ok(entries) = serde_json::from_value::<Vec<TimeEntry>>(value.clone()) {
+            let running = if entries.last().is_some_and(|e| e.end.is_none()) { ", en marcha" } else { "" };
+            let plural = if entries.len() == 1 { "" } else { "s" };
+            return format!("{} intervalo{}{}", entries.len(), plural, running);
+        },
+        _ => {},
+    }
+
+    match value {
+        Value::Null => "(vacío)".to_string(),
+        Value::Bool(true) => "sí".to_string(),
+        Value::Bool(false) => "no".to_string(),
+        Value::String(s) => match DateTime::parse_from_rfc3339(s) {
+            Ok(dt) => dt.with_timezone(&Local).format(DATE_FORMAT).to_string(),
+            Err(_) => format!("\"{}\"", s),
+        },
+        Value::Array(items) if items.is_empty() => "(ninguno)".to_string(),
+        Value::Array(items) => items.iter().map(|item| format_value("", item)).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_and_describes_changes() {
+        let path = std::env::temp_dir().join(format!("rustask-history-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let path = path.to_str().unwrap();
+
+        let mut tasks = vec![Task::new(1, "Uno".to_string(), None)];
+        let first = tasks[0].clone();
+        let mut history = History::open(path, &[]);
+        history.record(&tasks).unwrap();
+
+        tasks[0].title = "Uno bis".to_string();
+        tasks[0].add_tag("casa".to_string());
+        tasks[0].complete();
+        history.record(&tasks).unwrap();
+        // Sin cambios no se escribe nada
+        history.record(&tasks).unwrap();
+
+        // Al reabrir se sigue la numeración
+        let mut reopened = History::open(path, &tasks);
+        reopened.record(&[]).unwrap();
+
+        // Una tarea nueva con el mismo ID no hereda el historial
+        let reused = Task::new(1, "Otra".to_string(), None);
+        reopened.record(std::slice::from_ref(&reused)).unwrap();
+        // Cambiar de archivo no se registra como bajas y altas
+        reopened.rebaseline(&[]);
+        reopened.record(&[]).unwrap();
+
+        let events = read(path).unwrap();
+        assert_eq!(events.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(events.iter().filter(|e| belongs_to(e, &first)).count(), 3);
+        assert!(belongs_to(&events[3], &reused) && !belongs_to(&events[3], &first));
+        assert_eq!(describe(&events[0]), vec!["➕ Creada"]);
+        assert_eq!(describe(&events[2]), vec!["🗑️ Eliminada"]);
+
+        let changes = describe(&events[1]);
+        assert!(changes.contains(&"✏️ Título: \"Uno\" → \"Uno bis\"".to_string()));
+        assert!(changes.contains(&"✏️ Tags: (ninguno) → \"casa\"".to_string()));
+        assert!(changes.contains(&"✏️ Estado: pendiente → completada".to_string()));
+
+        fs::remove_file(path).unwrap();
+    }
+}
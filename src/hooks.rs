@@ -0,0 +1,326 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::config::Config;
+use crate::task::Task;
+
+// Operaciones que disparan hooks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookEvent {
+    Add,
+    Modify,
+    Complete,
+    Delete,
+}
+
+impl HookEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            HookEvent::Add => "add",
+            HookEvent::Modify => "modify",
+            HookEvent::Complete => "complete",
+            HookEvent::Delete => "delete",
+        }
+    }
+}
+
+// Ejecutables del directorio de hooks:
+//   on-<evento>*   antes de la operación; reciben la tarea en JSON por stdin y
+//                  pueden devolverla modificada por stdout o vetar la operación
+//                  saliendo con un código distinto de 0
+//   post-<evento>* después de la operación; solo reciben la tarea final
+// En on-modify se envían dos líneas: la tarea original y la modificada.
+pub struct Hooks {
+    dir: PathBuf,
+    timeout: Duration,
+}
+
+struct HookOutput {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+impl Hooks {
+    pub fn new(dir: &str, timeout: Duration) -> Self {
+        Self { dir: PathBuf::from(dir), timeout }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.hooks_dir, Duration::from_secs(config.hook_timeout_secs))
+    }
+
+    // Ejecuta los hooks previos en orden. Retorna la tarea (quizás modificada por
+    // los hooks) o el mensaje del hook que vetó la operación.
+    pub fn run_before(&self, event: HookEvent, original: Option<&Task>, mut task: Task) -> Result<Task, String> {
+        for hook in self.find_hooks(&format!("on-{}", event.name())) {
+            let mut input = String::new();
+            if let Some(original) = original {
+                input.push_str(&to_json_line(original)?);
+            }
+            input.push_str(&to_json_line(&task)?);
+
+            let output = self.execute(&hook, &input)?;
+            let (modified, messages) = parse_output(&output.stdout);
+
+            if !output.success {
+                let mut reason = messages.join("\n");
+                if reason.is_empty() {
+                    reason = output.stderr.trim().to_string();
+                }
+                if reason.is_empty() {
+                    reason = "operación rechazada".to_string();
+                }
+                return Err(format!("{}: {}", hook_name(&hook), reason));
+            }
+
+            for message in messages {
+                println!("🪝 {}", message);
+            }
+
+            if let Some(json) = modified {
+                let (id, uuid) = (task.id, task.uuid);
+                task = serde_json::from_str(&json)
+                    .map_err(|e| format!("{}: tarea inválida: {}", hook_name(&hook), e))?;
+                // Los hooks no pueden cambiar el ID ni el UUID
+                task.id = id;
+                task.uuid = uuid;
+            }
+        }
+
+        Ok(task)
+    }
+
+    // Ejecuta los hooks posteriores; sus errores solo se informan
+    pub fn run_after(&self, event: HookEvent, task: &Task) {
+        for hook in self.find_hooks(&format!("post-{}", event.name())) {
+            let result = to_json_line(task).and_then(|input| self.execute(&hook, &input));
+
+            match result {
+                Ok(output) => {
+                    for line in output.stdout.lines().filter(|l| !l.trim().is_empty()) {
+                        println!("🪝 {}", line);
+                    }
+                    if !output.success {
+                        eprintln!("⚠️ {} falló: {}", hook_name(&hook), output.stderr.trim());
+                    }
+                }
+                Err(e) => eprintln!("⚠️ {}", e),
+            }
+        }
+    }
+
+    // Ejecutables cuyo nombre empieza con `prefix`, ordenados por nombre
+    fn find_hooks(&self, prefix: &str) -> Vec<PathBuf> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut hooks: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(prefix))
+            })
+            .filter(|path| is_executable(path))
+            .collect();
+
+        hooks.sort();
+        hooks
+    }
+
+    fn execute(&self, hook: &Path, input: &str) -> Result<HookOutput, String> {
+        let mut child = Command::new(hook)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("{}: no se pudo ejecutar: {}", hook_name(hook), e))?;
+
+        // Leer la salida en otros hilos para que el hook no se bloquee escribiendo
+        let stdout = read_in_background(child.stdout.take().unwrap());
+        let stderr = read_in_background(child.stderr.take().unwrap());
+
+        // También se escribe en otro hilo: un hook que no lee stdin no es un error,
+        // pero con una tarea grande bloquearía la escritura antes del tiempo límite
+        if let Some(mut stdin) = child.stdin.take() {
+            let input = input.to_string();
+            thread::spawn(move || {
+                let _ = stdin.write_all(input.as_bytes());
+            });
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!("{}: superó el tiempo límite de {:?}", hook_name(hook), self.timeout));
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(20)),
+                Err(e) => return Err(format!("{}: {}", hook_name(hook), e)),
+            }
+        };
+
+        Ok(HookOutput {
+            success: status.success(),
+            stdout: collect_until(&stdout, deadline),
+            stderr: collect_until(&stderr, deadline),
+        })
+    }
+}
+
+// Manda lo que se va leyendo de `pipe` por un canal. Un proceso que el hook
+// deja en segundo plano (ej: 'notify-send ... &') puede mantener el pipe
+// abierto después de que el hook termine, así que no se espera al final.
+fn read_in_background<R: Read + Send + 'static>(mut pipe: R) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        while let Ok(read) = pipe.read(&mut buffer) {
+            if read == 0 || sender.send(buffer[..read].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+// Lo leído hasta que se cierra el pipe o hasta `deadline`, lo que llegue antes
+fn collect_until(receiver: &Receiver<Vec<u8>>, deadline: Instant) -> String {
+    let mut output = Vec::new();
+    while let Ok(chunk) = receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        output.extend(chunk);
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+fn to_json_line(task: &Task) -> Result<String, String> {
+    serde_json::to_string(task)
+        .map(|json| json + "\n")
+        .map_err(|e| e.to_string())
+}
+
+// La primera línea que sea un objeto JSON es la tarea; el resto son mensajes
+fn parse_output(stdout: &str) -> (Option<String>, Vec<String>) {
+    let mut task = None;
+    let mut messages = Vec::new();
+
+    for line in stdout.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        if task.is_none() && line.starts_with('{') {
+            task = Some(line.to_string());
+        } else {
+            messages.push(line.to_string());
+        }
+    }
+
+    (task, messages)
+}
+
+fn hook_name(hook: &Path) -> String {
+    hook.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| hook.display().to_string())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn hooks_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rustask-hooks-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_hook(dir: &Path, name: &str, script: &str) {
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_hook_can_veto_operation() {
+        let dir = hooks_dir("veto");
+        write_hook(&dir, "on-add-require-tag", r#"grep -q '"tags":\[\]' && { echo "La tarea necesita un tag"; exit 1; }; exit 0"#);
+        let hooks = Hooks::new(dir.to_str().unwrap(), Duration::from_secs(5));
+
+        let result = hooks.run_before(HookEvent::Add, None, Task::new(1, "Sin tag".to_string(), None));
+        assert_eq!(result.unwrap_err(), "on-add-require-tag: La tarea necesita un tag");
+
+        let tagged = Task::new_with_tags(1, "Con tag".to_string(), None, vec!["casa".to_string()]);
+        assert!(hooks.run_before(HookEvent::Add, None, tagged).is_ok());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_hook_can_modify_task() {
+        let dir = hooks_dir("modify");
+        // on-modify recibe la original y la modificada; devuelve la última con otro título
+        write_hook(&dir, "on-modify", r#"tail -n 1 | sed 's/"title":"[^"]*"/"title":"Cambiado"/'; echo "título cambiado""#);
+        let hooks = Hooks::new(dir.to_str().unwrap(), Duration::from_secs(5));
+
+        let original = Task::new(3, "Original".to_string(), None);
+        let mut modified = original.clone();
+        modified.title = "Nuevo".to_string();
+
+        let task = hooks.run_before(HookEvent::Modify, Some(&original), modified).unwrap();
+        assert_eq!(task.title, "Cambiado");
+        assert_eq!(task.id, 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_hook_timeout_vetoes_operation() {
+        let dir = hooks_dir("timeout");
+        write_hook(&dir, "on-delete", "sleep 5");
+        let hooks = Hooks::new(dir.to_str().unwrap(), Duration::from_millis(200));
+
+        // Una tarea más grande que el buffer del pipe no bloquea si el hook no lee stdin
+        let task = Task::new(1, "x".to_string(), Some("x".repeat(1 << 20)));
+        let started = Instant::now();
+        let result = hooks.run_before(HookEvent::Delete, None, task);
+        assert!(result.unwrap_err().contains("tiempo límite"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_background_process_does_not_block_hook() {
+        let dir = hooks_dir("background");
+        // El proceso en segundo plano hereda stdout y lo mantiene abierto
+        write_hook(&dir, "on-add", r#"sleep 5 & echo "Avisado"; exit 0"#);
+        let hooks = Hooks::new(dir.to_str().unwrap(), Duration::from_millis(300));
+
+        let started = Instant::now();
+        let result = hooks.run_before(HookEvent::Add, None, Task::new(1, "x".to_string(), None));
+        assert!(result.is_ok());
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
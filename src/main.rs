@@ -1,4 +1,5 @@
 use std::io::{self, Write};
+use std::collections::HashMap;
 use clap::Parser; 
 
 // Declarar nuestros módulos
@@ -6,50 +7,173 @@ mod task;
 mod storage;
 mod cli;
 mod scheduler;
+mod config;
+mod notifier;
+mod template;
+mod webhook;
+mod email;
+mod hooks;
+mod formats;
+mod migrations;
+mod backup;
+mod eventlog;
+mod history;
+mod timesheet;
+mod pomodoro;
+mod estimates;
+mod stats;
+mod charts;
+mod agenda;
+mod review;
+mod tui;
+mod repl;
 
 // Imports de nuestros módulos
-use std::sync::{Arc, Mutex};
-use cli::{Cli, Commands};
-use storage::{TaskStorage, TaskStats};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
+use chrono::{Datelike, Local, NaiveDate, Utc, Weekday};
+use cli::{BackupAction, ChartKind, Cli, Commands, DndAction, EmailAction, ExportFormat, ImportFormat, QuietHoursAction, SnoozePresetsAction, StorageAction, UpdateField, WebhookAction};
+use storage::{StorageFormat, TaskStorage};
 use scheduler::Scheduler;
-use task::Task;
+use config::{Config, EmailConfig, QuietWindow, SmtpSecurity, SnoozePreset, WebhookConfig};
+use repl::ReplHelper;
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::{CompletionType, Editor};
+use notifier::{Banners, Notification, Notifiers, TaskEvent, TerminalNotifier};
+use email::EmailNotifier;
+use hooks::{HookEvent, Hooks};
+use webhook::{Outbox, WebhookNotifier};
+use formats::report::{GroupBy, ReportStyle};
+use backup::Backups;
+use history::History;
+use pomodoro::Pomodoro;
+use task::{Priority, Task, TaskStatus};
+use uuid::Uuid;
 
 const TASKS_FILE: &str = "tasks.json";
+const CONFIG_FILE: &str = "config.json";
+const OUTBOX_FILE: &str = "outbox.json";
+const HISTORY_FILE: &str = "history.jsonl";
+// Comandos escritos en el modo interactivo (flechas y Ctrl-R)
+const COMMAND_HISTORY_FILE: &str = "command_history.txt";
+
+// Estado compartido entre el bucle interactivo y el scheduler
+struct App {
+    storage: Arc<Mutex<TaskStorage>>,
+    config: Arc<Mutex<Config>>,
+    notifiers: Arc<Notifiers>,
+    outbox: Arc<Mutex<Outbox>>,
+    // Fecha de modificación del archivo de tareas al cargarlo o guardarlo por última vez
+    tasks_modified: Mutex<Option<SystemTime>>,
+    history: Mutex<History>,
+    // Pomodoro en curso, compartido con el scheduler que lo hace avanzar
+    pomodoro: Arc<Mutex<Option<Pomodoro>>>,
+    // Avisos del scheduler mientras la TUI está abierta
+    banners: Banners,
+}
 
 fn main() {
     println!("🦀 RusTask - Interactive Mode");
     println!("Type 'exit' to quit\n");
     
-    // Crear el storage con Arc<Mutex> para compartirlo con el scheduler
-    let storage: Arc<Mutex<TaskStorage>> = Arc::new(Mutex::new(TaskStorage::new()));
-    
-    // Cargar tareas del archivo al inicio
-    {
-        let mut storage_lock = storage.lock().unwrap();
-        match storage_lock.load_from_file(TASKS_FILE) {
-            Ok(_) => {
-                let stats = storage_lock.get_stats();
-                if stats.total > 0 {
-                    println!("📂 Cargadas {} tareas desde {}", stats.total, TASKS_FILE);
-                }
+    // Cargar la configuración (horas de silencio, no molestar, formato de archivo...)
+    let config = match Config::load_from_file(CONFIG_FILE) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("⚠️ Error al cargar configuración: {}", e);
+            Config::default()
+        }
+    };
+    
+    // Cargar tareas del archivo al inicio, en el formato configurado
+    let mut storage = TaskStorage::new();
+    let path = tasks_file(&config);
+    match storage.load(config.storage_format, &path) {
+        Ok(_) => {
+            let stats = storage.get_stats();
+            if stats.total > 0 {
+                println!("📂 Cargadas {} tareas desde {}", stats.total, path);
             }
-            Err(e) => {
-                eprintln!("⚠️ Error al cargar tareas: {}", e);
+        }
+        Err(e) => {
+            eprintln!("❌ Error al cargar tareas desde {}: {}", path, e);
+            
+            // Seguir con una lista vacía sobrescribiría el archivo al guardar
+            match recover_from_backup(&config, &path) {
+                Some(recovered) => storage = recovered,
+                None => {
+                    eprintln!("   rustask se cierra sin modificar el archivo");
+                    std::process::exit(1);
+                }
             }
         }
     }
     
+    let history = Mutex::new(History::open(HISTORY_FILE, storage.get_all_tasks()));
+    
+    // Crear el storage con Arc<Mutex> para compartirlo con el scheduler
+    let storage: Arc<Mutex<TaskStorage>> = Arc::new(Mutex::new(storage));
+    let tasks_modified = Mutex::new(modified_time(&path));
+    let config: Arc<Mutex<Config>> = Arc::new(Mutex::new(config));
+    
+    // Cola de webhooks pendientes de la sesión anterior
+    let outbox = match Outbox::load_from_file(OUTBOX_FILE) {
+        Ok(outbox) => outbox,
+        Err(e) => {
+            eprintln!("⚠️ Error al cargar la cola de webhooks: {}", e);
+            Outbox::default()
+        }
+    };
+    let outbox: Arc<Mutex<Outbox>> = Arc::new(Mutex::new(outbox));
+    
+    // Canales de notificación
+    let banners = Banners::default();
+    let notifiers = Arc::new(Notifiers::new(vec![
+        Box::new(TerminalNotifier::new(banners.clone())),
+        Box::new(WebhookNotifier::new(Arc::clone(&outbox))),
+        Box::new(EmailNotifier),
+    ]));
+    
+    let pomodoro = Arc::new(Mutex::new(None));
+    let app = App { storage, config, notifiers, outbox, tasks_modified, history, pomodoro, banners };
+    
     // Iniciar el scheduler
-    let scheduler = Scheduler::new(Arc::clone(&storage));
+    let scheduler = Scheduler::new(Arc::clone(&app.storage), Arc::clone(&app.config), Arc::clone(&app.notifiers),
+                                   Arc::clone(&app.pomodoro));
     scheduler.start();
     
+    let mut editor = match new_editor(&app) {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("❌ No se pudo iniciar el editor de línea: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
     loop {
-        print!("\nrustask> ");
-        io::stdout().flush().unwrap();
-        
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-        let input = input.trim();   
+        println!();
+        let input = match editor.readline("rustask> ") {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    let _ = editor.add_history_entry(line.trim());
+                    // Se guarda al momento, así no se pierde si rustask termina de golpe
+                    if let Err(e) = editor.append_history(COMMAND_HISTORY_FILE) {
+                        eprintln!("⚠️ Error al guardar el historial de comandos: {}", e);
+                    }
+                }
+                line
+            },
+            // Ctrl-C descarta la línea, como en un shell
+            Err(ReadlineError::Interrupted) => continue,
+            // Ctrl-D o fin de la entrada: salir guardando, igual que con 'exit'
+            Err(ReadlineError::Eof) => "exit".to_string(),
+            Err(e) => {
+                eprintln!("❌ Error al leer la entrada: {}", e);
+                "exit".to_string()
+            }
+        };
+        let input = input.trim();
 
         if input.is_empty() {
             continue;
@@ -60,28 +184,41 @@ fn main() {
             scheduler.stop();
             
             // Guardar tareas antes de salir
-            let storage_lock = storage.lock().unwrap();
-            if let Err(e) = storage_lock.save_to_file(TASKS_FILE) {
-                eprintln!("⚠️ Error al guardar tareas: {}", e);
-            } else {
-                println!("\n💾 Tareas guardadas en {}", TASKS_FILE);
+            if let Some(path) = save_tasks(&app) {
+                println!("\n💾 Tareas guardadas en {}", path);
+            }
+            
+            // El scheduler también modifica la configuración (ej: última agenda enviada)
+            if let Err(e) = app.config.lock().unwrap().save_to_file(CONFIG_FILE) {
+                eprintln!("⚠️ Error al guardar configuración: {}", e);
             }
             println!("👋 ¡Hasta luego!");
             break;
         }
         
-        let args = parse_args(input);
+        let args = match cli::parse_args(input) {
+            Ok(args) => args,
+            Err(e) => {
+                println!("❌ {}", e);
+                continue;
+            }
+        };
         let mut full_args = vec!["rustask".to_string()];
         full_args.extend(args);
 
         match Cli::try_parse_from(full_args) {
           Ok(cli) => {
-              handle_command(cli.command, &storage);
+              // El archivo pudo cambiar fuera de rustask (ej: un editor de todo.txt)
+              reload_if_changed(&app);
+              
+              handle_command(cli.command, &app);
               
               // Guardar tareas después de cada comando
-              let storage_lock = storage.lock().unwrap();
-              if let Err(e) = storage_lock.save_to_file(TASKS_FILE) {
-                  eprintln!("⚠️ Error al guardar tareas: {}", e);
+              save_tasks(&app);
+              
+              let config_lock = app.config.lock().unwrap();
+              if let Err(e) = config_lock.save_to_file(CONFIG_FILE) {
+                  eprintln!("⚠️ Error al guardar configuración: {}", e);
               }
           },
           Err(e) => {
@@ -91,116 +228,396 @@ fn main() {
     }
 }
 
-fn handle_command(command: Commands, storage: &Arc<Mutex<TaskStorage>>) {
-    let mut storage = storage.lock().unwrap();
+// Editor de línea con historial persistente y autocompletado (ver repl.rs)
+fn new_editor(app: &App) -> rustyline::Result<Editor<ReplHelper, DefaultHistory>> {
+    let config = rustyline::Config::builder()
+        .max_history_size(1000)?
+        .history_ignore_dups(true)?
+        .completion_type(CompletionType::List)
+        .build();
+    
+    let mut editor = Editor::with_config(config)?;
+    editor.set_helper(Some(ReplHelper::new(Arc::clone(&app.storage))));
+    // La primera vez el archivo no existe
+    let _ = editor.load_history(COMMAND_HISTORY_FILE);
+    Ok(editor)
+}
 
+fn handle_command(command: Commands, app: &App) {
+    // Cada comando bloquea solo lo que usa. Los que ejecutan hooks reciben `app`
+    // y sueltan las tareas mientras corre el hook, así un hook lento no frena
+    // al scheduler.
     match command {
-        Commands::Add { title, description, tags } => {
-            handle_add(&mut storage, title, description, tags);
+        Commands::Add { title, description, tags, priority, project, due, estimate } => {
+            // add "" llega como título vacío
+            if title.trim().is_empty() {
+                println!("❌ El título no puede estar vacío");
+                return;
+            }
+            let mut task = if tags.is_empty() {
+                // Sin tags, usar constructor básico
+                Task::new(0, title, description)
+            } else {
+                // Con tags, usar constructor con tags
+                Task::new_with_tags(0, title, description, tags)
+            };
+            task.priority = priority;
+            task.project = project;
+            task.due = due;
+            task.estimate_minutes = estimate.map(|e| e.num_minutes());
+            
+            handle_add(app, task);
         },
         Commands::List { completed, pending, tag } => {
-            handle_list(&storage, completed, pending, tag);
+            handle_list(&app.storage.lock().unwrap(), completed, pending, tag);
         },
         Commands::Complete { id } => {
-            handle_complete(&mut storage, id);
+            let Some(id) = resolve_id(&app.storage.lock().unwrap(), &id) else { return };
+            handle_complete(app, id);
         },
         Commands::Delete { id } => {
-            handle_delete(&mut storage, id);
+            let Some(id) = resolve_id(&app.storage.lock().unwrap(), &id) else { return };
+            handle_delete(app, id);
+        },
+        Commands::Stats { window, weeks, json } => {
+            let (storage, config) = lock(app);
+            handle_stats(&storage, &config, window, weeks, json);
         },
-        Commands::Stats => {
-            handle_stats(&storage);
+        Commands::Chart { kind } => {
+            handle_chart(&app.storage.lock().unwrap(), kind);
         },
-        Commands::Show { id } => {
+        Commands::Show { id, history } => {
+            let (storage, config) = lock(app);
+            let Some(id) = resolve_id(&storage, &id) else { return };
             handle_show(&storage, id);
+            if let Some(task) = storage.find_task_by_id(id).filter(|_| history) {
+                handle_task_history(&config, task);
+            }
         },
-        Commands::Update { id, title, description, tags } => {
-            handle_update(&mut storage, id, title, description, tags);
+        Commands::Update { id, title, description, tags, priority, project, due, estimate, clear } => {
+            let Some(id) = resolve_id(&app.storage.lock().unwrap(), &id) else { return };
+            handle_update(app, id, title, description, tags, priority, project, due, estimate, clear);
+            if estimate.is_some() {
+                let (storage, config) = lock(app);
+                warn_if_over_capacity(&storage, &config, id);
+            }
         },
         Commands::AddTag { id, tag } => {
-            handle_add_tag(&mut storage, id, tag);
+            let Some(id) = resolve_id(&app.storage.lock().unwrap(), &id) else { return };
+            handle_add_tag(app, id, tag);
         },
         Commands::RemoveTag { id, tag } => {
-            handle_remove_tag(&mut storage, id, tag);
+            let Some(id) = resolve_id(&app.storage.lock().unwrap(), &id) else { return };
+            handle_remove_tag(app, id, tag);
         },
         Commands::ClearTags { id } => {
-            handle_clear_tags(&mut storage, id);
+            let Some(id) = resolve_id(&app.storage.lock().unwrap(), &id) else { return };
+            handle_clear_tags(app, id);
         },
         Commands::Schedule { id, datetime } => {
-            handle_schedule(&mut storage, id, datetime);
+            let Some(id) = resolve_id(&app.storage.lock().unwrap(), &id) else { return };
+            handle_schedule(app, id, datetime);
+            let (storage, config) = lock(app);
+            warn_if_over_capacity(&storage, &config, id);
         },
-        Commands::Snooze { id, minutes } => {
-            handle_snooze(&mut storage, id, minutes);
+        Commands::Snooze { id, when } => {
+            let Some(id) = resolve_id(&app.storage.lock().unwrap(), &id) else { return };
+            handle_snooze(app, id, when);
+        },
+        Commands::SnoozePresets { action } => {
+            handle_snooze_presets(&mut app.config.lock().unwrap(), action);
         },
         Commands::Scheduled => {
-            handle_scheduled(&storage);
+            handle_scheduled(&app.storage.lock().unwrap());
+        },
+        // Esperan respuestas del usuario: bloquean las tareas solo al aplicar cada una
+        Commands::Review { weekly } => {
+            handle_review(app, weekly);
+        },
+        Commands::Tui => {
+            tui::run(app);
+        },
+        Commands::Agenda { week } => {
+            handle_agenda(&app.storage.lock().unwrap(), week);
+        },
+        Commands::Calendar { month } => {
+            handle_calendar(&app.storage.lock().unwrap(), month);
+        },
+        Commands::Dnd { action } => {
+            handle_dnd(&mut app.config.lock().unwrap(), action);
+        },
+        Commands::QuietHours { action } => {
+            handle_quiet_hours(&mut app.config.lock().unwrap(), action);
+        },
+        Commands::Webhook { action } => {
+            handle_webhook(&mut app.config.lock().unwrap(), &app.outbox, action);
+        },
+        Commands::Email { action } => {
+            let (storage, mut config) = lock(app);
+            handle_email(&storage, &mut config, action);
+        },
+        Commands::Export { format, output, group_by, template } => {
+            handle_export(&app.storage.lock().unwrap(), format, output, group_by, template);
+        },
+        Commands::Import { format, file, dry_run, map } => {
+            handle_import(app, format, file, dry_run, map);
+        },
+        Commands::Storage { action } => {
+            let (mut storage, mut config) = lock(app);
+            handle_storage(&mut storage, &mut config, action);
+            app.history.lock().unwrap().rebaseline(storage.get_all_tasks());
+        },
+        Commands::Backup { action } => {
+            let (mut storage, config) = lock(app);
+            handle_backup(&mut storage, &config, action);
+            app.history.lock().unwrap().rebaseline(storage.get_all_tasks());
+        },
+        Commands::Compact => {
+            let (mut storage, config) = lock(app);
+            handle_compact(&mut storage, &config);
+        },
+        Commands::Estimates => {
+            handle_estimates(&app.storage.lock().unwrap());
+        },
+        Commands::Capacity { days } => {
+            let (storage, config) = lock(app);
+            handle_capacity(&storage, &config, days);
+        },
+        Commands::Log { from, to } => {
+            let (storage, config) = lock(app);
+            handle_log(&storage, &config, from, to);
+        },
+        Commands::Start { id } => {
+            let Some(id) = resolve_id(&app.storage.lock().unwrap(), &id) else { return };
+            handle_start(app, id);
+        },
+        Commands::Stop => {
+            handle_stop(app);
+        },
+        Commands::Pomodoro { id, stop } => {
+            let (storage, config) = lock(app);
+            let mut pomodoro = app.pomodoro.lock().unwrap();
+            match id {
+                Some(id) => {
+                    let Some(id) = resolve_id(&storage, &id) else { return };
+                    handle_pomodoro_start(&storage, &config, &mut pomodoro, id);
+                },
+                None if stop => handle_pomodoro_stop(&mut pomodoro),
+                None => handle_pomodoro_status(&storage, &pomodoro),
+            }
+        },
+        Commands::Timesheet { from, to, by, csv } => {
+            handle_timesheet(&app.storage.lock().unwrap(), from, to, by, csv);
         },
     }
 }
 
-// Parser simple de comillas (sin dependencias)
-fn parse_args(input: &str) -> Vec<String> {
-    let mut args = Vec::new();
-    let mut current_arg = String::new();
-    let mut in_quotes = false;
+// Bloquea tareas y configuración, siempre en este orden (igual que el scheduler)
+fn lock(app: &App) -> (MutexGuard<'_, TaskStorage>, MutexGuard<'_, Config>) {
+    let storage = app.storage.lock().unwrap();
+    let config = app.config.lock().unwrap();
+    (storage, config)
+}
 
-    for c in input.chars() {
-        match c {
-            '"' => {
-                // Toggle estado de comillas
-                in_quotes = !in_quotes;
-            }
-            ' ' if !in_quotes => {
-                // Espacio fuera de comillas = separador
-                if !current_arg.is_empty() {
-                    args.push(current_arg.clone());
-                    current_arg.clear();
-                }
-            }
-            _ => {
-                // Cualquier otro carácter se agrega al argumento actual
-                current_arg.push(c);
+fn app_hooks(app: &App) -> Hooks {
+    Hooks::from_config(&app.config.lock().unwrap())
+}
+
+// Archivo principal de tareas según el formato configurado
+fn tasks_file(config: &Config) -> String {
+    match config.storage_format {
+        StorageFormat::Json => TASKS_FILE.to_string(),
+        StorageFormat::TodoTxt => config.todo_txt_file.clone(),
+        StorageFormat::EventLog => config.event_log_file.clone(),
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// Guarda las tareas en el archivo configurado; retorna la ruta si se guardó.
+// Si el contenido cambió, el anterior se guarda como copia de seguridad.
+fn save_tasks(app: &App) -> Option<String> {
+    let (mut storage, config) = lock(app);
+    let path = tasks_file(&config);
+    let previous = if config.storage_format == StorageFormat::EventLog { None } else { std::fs::read(&path).ok() };
+    
+    if let Err(e) = storage.save(config.storage_format, &path) {
+        eprintln!("⚠️ Error al guardar tareas: {}", e);
+        return None;
+    }
+    
+    // Con 'eventlog' el propio registro ya es el historial
+    if config.storage_format != StorageFormat::EventLog {
+        if let Err(e) = app.history.lock().unwrap().record(storage.get_all_tasks()) {
+            eprintln!("⚠️ Error al guardar el historial: {}", e);
+        }
+    }
+    
+    // El registro de eventos ya conserva los estados anteriores
+    let backups_enabled = config.backup_count > 0 && config.storage_format != StorageFormat::EventLog;
+    if let Some(previous) = previous.filter(|_| backups_enabled) {
+        if std::fs::read(&path).ok().as_ref() != Some(&previous) {
+            if let Err(e) = Backups::from_config(&config).save(&path, &previous) {
+                eprintln!("⚠️ Error al guardar la copia de seguridad: {}", e);
             }
         }
     }
+    
+    *app.tasks_modified.lock().unwrap() = modified_time(&path);
+    Some(path)
+}
+
+// Busca la copia válida más reciente y pregunta si usarla
+fn recover_from_backup(config: &Config, path: &str) -> Option<TaskStorage> {
+    for backup in Backups::from_config(config).list(path) {
+        let mut storage = TaskStorage::new();
+        if storage.load(config.storage_format, &backup.path.to_string_lossy()).is_err() {
+            continue;
+        }
+        
+        println!("💾 La copia válida más reciente es del {} ({} tareas)",
+                 backup.created_at.format("%d/%m/%Y %H:%M:%S"), storage.get_stats().total);
+        print!("¿Recuperar las tareas desde esa copia? [s/N] ");
+        io::stdout().flush().unwrap();
+        
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer).unwrap();
+        if !matches!(answer.trim().to_lowercase().as_str(), "s" | "si" | "sí" | "y" | "yes") {
+            return None;
+        }
+        
+        // El archivo dañado se aparta para poder revisarlo
+        let damaged = format!("{}.damaged", path);
+        if let Err(e) = std::fs::rename(path, &damaged) {
+            eprintln!("⚠️ No se pudo apartar {}: {}", path, e);
+            return None;
+        }
+        println!("♻️ Tareas recuperadas. El archivo dañado quedó en {}", damaged);
+        return Some(storage);
+    }
+    
+    eprintln!("   No hay copias de seguridad válidas en {}", config.backup_dir);
+    None
+}
 
-    // No olvidar el último argumento
-    if !current_arg.is_empty() {
-        args.push(current_arg);
+// Vuelve a cargar las tareas si otro programa modificó el archivo
+fn reload_if_changed(app: &App) {
+    let (mut storage, config) = lock(app);
+    let path = tasks_file(&config);
+    let modified = modified_time(&path);
+    let mut last_modified = app.tasks_modified.lock().unwrap();
+    
+    if modified.is_none() || modified == *last_modified {
+        return;
+    }
+    
+    match storage.load(config.storage_format, &path) {
+        Ok(()) => println!("🔄 {} cambió fuera de rustask, tareas recargadas", path),
+        Err(e) => eprintln!("⚠️ Error al recargar tareas: {}", e),
     }
+    *last_modified = modified;
+}
 
-    args
+// Convierte el ID o prefijo de UUID escrito por el usuario en el ID corto
+fn resolve_id(storage: &TaskStorage, reference: &str) -> Option<u64> {
+    match storage.resolve_id(reference) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            println!("❌ {}", e);
+            None
+        }
+    }
 }
 
 // Manejar comando: add
-fn handle_add(storage: &mut TaskStorage, title: String, description: Option<String>, tags: Vec<String>) {
-    let task = if tags.is_empty() {
-        // Sin tags, usar constructor básico
-        Task::new(0, title, description)
-    } else {
-        // Con tags, usar constructor con tags
-        Task::new_with_tags(0, title, description, tags)
+fn handle_add(app: &App, task: Task) {
+    let hooks = app_hooks(app);
+    // Los hooks on-add pueden modificar la tarea o rechazarla
+    let task = match hooks.run_before(HookEvent::Add, None, task) {
+        Ok(task) => task,
+        Err(e) => {
+            print_hook_veto(&e);
+            return;
+        }
+    };
+    
+    let task = {
+        let mut storage = app.storage.lock().unwrap();
+        let id = storage.add_task(task);
+        storage.find_task_by_id(id).cloned()
     };
     
-    let id = storage.add_task(task);
-    println!("✅ Tarea creada con ID: {}", id);
+    if let Some(task) = task {
+        println!("✅ Tarea creada con ID: {}", task.id);
+        hooks.run_after(HookEvent::Add, &task);
+        app.notifiers.notify(&app.config.lock().unwrap(), TaskEvent::Added, &task);
+    }
+}
+
+fn print_hook_veto(reason: &str) {
+    println!("{}", hook_veto_message(reason));
+}
+
+fn hook_veto_message(reason: &str) -> String {
+    format!("🚫 Operación cancelada por un hook: {}", reason)
+}
+
+fn task_not_found(id: u64) -> String {
+    format!("❌ No se encontró una tarea con ID {}", id)
+}
+
+// Aplica `change` a una copia de la tarea y la guarda si los hooks on-modify lo permiten.
+// Retorna false si la tarea no existe o un hook rechazó el cambio (ya informado).
+fn modify_task<F: FnOnce(&mut Task)>(app: &App, id: u64, change: F) -> bool {
+    match try_modify_task(app, id, change) {
+        Ok(()) => true,
+        Err(e) => {
+            println!("{}", e);
+            false
+        }
+    }
+}
+
+// Como modify_task, pero sin imprimir: el error es el mensaje para el usuario.
+// Las tareas no quedan bloqueadas mientras corren los hooks.
+fn try_modify_task<F: FnOnce(&mut Task)>(app: &App, id: u64, change: F) -> Result<(), String> {
+    let hooks = app_hooks(app);
+    let original = app.storage.lock().unwrap().find_task_by_id(id).cloned().ok_or_else(|| task_not_found(id))?;
+    
+    let mut task = original.clone();
+    change(&mut task);
+    
+    let task = hooks.run_before(HookEvent::Modify, Some(&original), task).map_err(|e| hook_veto_message(&e))?;
+    save_hooked_task(app, task)
+        .map(|task| hooks.run_after(HookEvent::Modify, &task))
+}
+
+// Guarda la tarea que devolvieron los hooks; pudo eliminarse mientras corrían
+fn save_hooked_task(app: &App, task: Task) -> Result<Task, String> {
+    let id = task.id;
+    let mut storage = app.storage.lock().unwrap();
+    if !storage.update_task(task) {
+        return Err(task_not_found(id));
+    }
+    storage.find_task_by_id(id).cloned().ok_or_else(|| task_not_found(id))
 }
 
 // Manejar comando: list  
 fn handle_list(storage: &TaskStorage, completed: bool, pending: bool, tag: Option<String>) {
-    let all_tasks = storage.get_all_tasks();
-    
     let tasks: Vec<&Task> = if completed && !pending {
         // Solo completadas
-        all_tasks.iter().filter(|t| t.is_completed()).collect()
+        storage.get_tasks_by_status(true).collect()
     } else if pending && !completed {
         // Solo pendientes
-        all_tasks.iter().filter(|t| !t.is_completed()).collect()
+        storage.get_tasks_by_status(false).collect()
     } else if let Some(ref tag_filter) = tag {
         // Filtrar por tag
-        all_tasks.iter().filter(|t| t.has_tag(tag_filter)).collect()
+        storage.list_tasks_by_tag(tag_filter).collect()
     } else {
         // Todas las tareas
-        all_tasks.iter().collect()
+        storage.get_all_tasks().iter().collect()
     };
     
     if tasks.is_empty() {
@@ -217,7 +634,7 @@ fn handle_list(storage: &TaskStorage, completed: bool, pending: bool, tag: Optio
             format!(" [{}]", task.get_tags().join(", "))
         };
         
-        println!("{} [{}] {}{}", status, task.id, task.title, tags_str);
+        println!("{} [{}] {}{}{}", status, task.id, priority_icon(task.priority), task.title, tags_str);
         
         if let Some(ref desc) = task.description {
             println!("    📄 {}", desc);
@@ -225,28 +642,76 @@ fn handle_list(storage: &TaskStorage, completed: bool, pending: bool, tag: Optio
     }
 }
 
+fn priority_icon(priority: Option<Priority>) -> &'static str {
+    match priority {
+        Some(Priority::High) => "🔴 ",
+        Some(Priority::Medium) => "🟡 ",
+        Some(Priority::Low) => "🟢 ",
+        None => "",
+    }
+}
+
 // Manejar comando: complete
-fn handle_complete(storage: &mut TaskStorage, id: u64) {
-    if storage.complete_task(id) {
-        println!("✅ Tarea {} marcada como completada", id);
-    } else {
-        println!("❌ No se encontró una tarea con ID {}", id);
+fn handle_complete(app: &App, id: u64) {
+    match complete_task(app, id) {
+        Ok(()) => println!("✅ Tarea {} marcada como completada", id),
+        Err(e) => println!("{}", e),
     }
 }
 
+fn complete_task(app: &App, id: u64) -> Result<(), String> {
+    let hooks = app_hooks(app);
+    let original = app.storage.lock().unwrap().find_task_by_id(id).cloned().ok_or_else(|| task_not_found(id))?;
+    
+    // Los hooks on-complete reciben la tarea ya completada; pueden modificarla
+    // (ej: agregar un tag) pero no deshacer el completado
+    let mut task = original.clone();
+    task.complete();
+    let completed_at = task.completed_at;
+    let mut task = hooks.run_before(HookEvent::Complete, Some(&original), task).map_err(|e| hook_veto_message(&e))?;
+    
+    // Se respeta el completed_at que devuelva el hook
+    task.status = TaskStatus::Completed;
+    task.completed_at = task.completed_at.or(completed_at);
+    let task = save_hooked_task(app, task)?;
+    
+    hooks.run_after(HookEvent::Complete, &task);
+    app.notifiers.notify(&app.config.lock().unwrap(), TaskEvent::Completed, &task);
+    Ok(())
+}
+
 // Manejar comando: delete  
-fn handle_delete(storage: &mut TaskStorage, id: u64) {
-    if storage.delete_task(id) {
-        println!("🗑️ Tarea {} eliminada", id);
-    } else {
+fn handle_delete(app: &App, id: u64) {
+    let hooks = app_hooks(app);
+    let Some(task) = app.storage.lock().unwrap().find_task_by_id(id).cloned() else {
         println!("❌ No se encontró una tarea con ID {}", id);
+        return;
+    };
+    
+    if let Err(e) = hooks.run_before(HookEvent::Delete, None, task.clone()) {
+        print_hook_veto(&e);
+        return;
+    }
+    
+    if app.storage.lock().unwrap().delete_task(id) {
+        println!("🗑️ Tarea {} eliminada", id);
+        hooks.run_after(HookEvent::Delete, &task);
     }
 }
 
 // Manejar comando: stats
-fn handle_stats(storage: &TaskStorage) {
-    let stats: TaskStats = storage.get_stats();
+fn handle_stats(storage: &TaskStorage, config: &Config, window: u32, weeks: u32, json: bool) {
+    let report = stats::build(storage.get_stats(), storage.get_all_tasks(), config, Local::now(), window, weeks);
+    
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("❌ Error al generar JSON: {}", e),
+        }
+        return;
+    }
     
+    let stats = &report.counts;
     println!("📊 Estadísticas de tareas:");
     println!("   📝 Total: {}", stats.total);
     println!("   ✅ Completadas: {}", stats.completed);
@@ -256,6 +721,103 @@ fn handle_stats(storage: &TaskStorage) {
         let completion_rate = (stats.completed as f64 / stats.total as f64) * 100.0;
         println!("   🎯 Progreso: {:.1}%", completion_rate);
     }
+    if report.overdue > 0 {
+        println!("   🔴 Vencidas: {}", report.overdue);
+    }
+    if let Some(ref lead_time) = report.lead_time {
+        println!("   ⏱️ Tiempo hasta completar: {} de media, {} de mediana",
+                 stats::format_hours(lead_time.average_hours), stats::format_hours(lead_time.median_hours));
+    }
+    
+    println!("\n📅 Completadas por día (últimos {} días):", window);
+    for day in &report.completed_per_day {
+        println!("   {} {}: {}", weekday_name(day.date.weekday()), day.date.format("%d/%m"), day.count);
+    }
+    
+    println!("\n🗓️ Completadas por semana:");
+    for week in &report.completed_per_week {
+        println!("   {}: {}", week.week, week.count);
+    }
+    
+    let trend = &report.trend;
+    println!("\n📈 Tendencia (últimos {} días frente a los {} anteriores):", trend.window_days, trend.window_days);
+    println!("   ➕ Creadas: {} ({})", trend.current.created, change(trend.current.created, trend.previous.created));
+    println!("   ✅ Completadas: {} ({})", trend.current.completed, change(trend.current.completed, trend.previous.completed));
+    if let Some(hours) = trend.current.average_lead_time_hours {
+        let previous = trend.previous.average_lead_time_hours
+            .map_or("sin datos antes".to_string(), |previous| format!("antes {}", stats::format_hours(previous)));
+        println!("   ⏱️ Tiempo hasta completar: {} ({})", stats::format_hours(hours), previous);
+    }
+    
+    if !report.tags.is_empty() {
+        println!("\n🏷️ Por tag:");
+        for tag in &report.tags {
+            let lead_time = tag.average_lead_time_hours
+                .map_or(String::new(), |hours| format!(", {} de media", stats::format_hours(hours)));
+            let overdue = if tag.overdue > 0 { format!(", {} vencidas", tag.overdue) } else { String::new() };
+            println!("   {}: {}/{} completadas{}{}", tag.tag, tag.completed, tag.total, overdue, lead_time);
+        }
+    }
+    
+    let snoozes = &report.snoozes;
+    if snoozes.total > 0 {
+        println!("\n😴 Snoozes: {} en {} tareas ({:.1} por tarea, {} por encima del límite)",
+                 snoozes.total, snoozes.snoozed_tasks, snoozes.average_per_snoozed_task, snoozes.escalated);
+    }
+    
+    let pomodoros = &report.pomodoros;
+    if pomodoros.total > 0 {
+        println!("\n🍅 Pomodoros: {}", pomodoros.total);
+        println!("   Por tarea:");
+        for task in &pomodoros.per_task {
+            println!("      [{}] {}: {}", task.id, task.title, task.count);
+        }
+        // Los últimos 7 días con pomodoros
+        println!("   Por día:");
+        for day in pomodoros.per_day.iter().rev().take(7) {
+            println!("      {}: {}", day.date.format("%d/%m/%Y"), day.count);
+        }
+    }
+}
+
+// "+3", "-1", "="
+fn change(current: usize, previous: usize) -> String {
+    match current.cmp(&previous) {
+        std::cmp::Ordering::Greater => format!("+{}", current - previous),
+        std::cmp::Ordering::Less => format!("-{}", previous - current),
+        std::cmp::Ordering::Equal => "=".to_string(),
+    }
+}
+
+// Manejar comando: chart
+fn handle_chart(storage: &TaskStorage, kind: ChartKind) {
+    let tasks = storage.get_all_tasks();
+    let today = Local::now().date_naive();
+    
+    let lines = match kind {
+        ChartKind::Burndown { days } => {
+            println!("📉 Tareas pendientes (últimos {} días):\n", days);
+            let from = today - chrono::Duration::days(days as i64 - 1);
+            charts::render_burndown(&charts::burndown(tasks, from, today), 8)
+        },
+        ChartKind::Heatmap { weeks } => {
+            println!("🟩 Completadas por día (últimas {} semanas):\n", weeks);
+            charts::heatmap(tasks, weeks, today)
+        },
+        ChartKind::Tags => {
+            let tags = stats::tag_stats(tasks, Local::now());
+            if tags.is_empty() {
+                println!("🏷️ No hay tareas con tags");
+                return;
+            }
+            println!("🏷️ Completadas / pendientes por tag:\n");
+            charts::tag_bars(&tags, 30)
+        },
+    };
+    
+    for line in lines {
+        println!("   {}", line);
+    }
 }
 
 // Manejar comando: show
@@ -264,6 +826,7 @@ fn handle_show(storage: &TaskStorage, id: u64) {
         let status = if task.is_completed() { "✅ Completada" } else { "⏳ Pendiente" };
         
         println!("🔍 Detalles de la tarea {}:\n", id);
+        println!("   🆔 UUID: {}", task.uuid);
         println!("   📝 Título: {}", task.title);
         println!("   📊 Estado: {}", status);
         
@@ -275,110 +838,498 @@ fn handle_show(storage: &TaskStorage, id: u64) {
             println!("   🏷️ Tags: {}", task.get_tags().join(", "));
         }
         
+        if let Some(priority) = task.priority {
+            println!("   {}Prioridad: {}", priority_icon(Some(priority)), priority.name());
+        }
+        
+        if let Some(ref project) = task.project {
+            println!("   📁 Proyecto: {}", project);
+        }
+        
+        if let Some(due) = task.due {
+            println!("   📆 Fecha límite: {}", due.format("%d/%m/%Y %H:%M"));
+        }
+        
         println!("   📅 Creada: {}", task.created_at.format("%Y-%m-%d %H:%M:%S"));
         
         if let Some(completed_at) = task.completed_at {
             println!("   ✅ Completada: {}", completed_at.format("%Y-%m-%d %H:%M:%S"));
         }
-    } else {
-        println!("❌ No se encontró una tarea con ID {}", id);
-    }
-}
-
-// Manejar comando: update
-fn handle_update(storage: &mut TaskStorage, id: u64, title: Option<String>, description: Option<String>, tags: Vec<String>) {
-    if let Some(mut task) = storage.find_task_by_id(id).cloned() {
-        // Actualizar campos si se proporcionan
-        if let Some(new_title) = title {
-            task.title = new_title;
+        
+        if let Some(reviewed) = task.last_reviewed {
+            println!("   🔎 Última revisión: {}", reviewed.format("%Y-%m-%d %H:%M:%S"));
         }
         
-        // Para description, necesitamos manejar el caso especial donde el usuario quiere limpiarla
-        if description.is_some() {
-            task.description = description;
+        if !task.time_entries.is_empty() {
+            let running = if task.is_tracking() { " (en marcha)" } else { "" };
+            println!("   ⏱️ Tiempo registrado: {}{}", timesheet::format_duration(task.tracked_time(Utc::now())), running);
         }
         
-        // Reemplazar tags si se proporcionan
-        if !tags.is_empty() {
-            task.clear_tags();
-            for tag in tags {
-                task.add_tag(tag);
-            }
+        if let Some(estimate) = task.estimate() {
+            let progress = match estimate.num_minutes() {
+                0 => String::new(),
+                minutes => format!(" ({:.0}% usado)", task.tracked_time(Utc::now()).num_minutes() as f64 / minutes as f64 * 100.0),
+            };
+            println!("   📐 Estimación: {}{}", timesheet::format_duration(estimate), progress);
         }
         
-        if storage.update_task(task) {
-            println!("✅ Tarea {} actualizada exitosamente", id);
-        } else {
-            println!("❌ Error al actualizar la tarea {}", id);
+        if !task.extras.is_empty() {
+            let keys: Vec<&str> = task.extras.keys().map(|k| k.as_str()).collect();
+            println!("   🧩 Atributos importados: {}", keys.join(", "));
         }
     } else {
         println!("❌ No se encontró una tarea con ID {}", id);
     }
 }
 
-// Manejar comando: add-tag
-fn handle_add_tag(storage: &mut TaskStorage, id: u64, tag: String) {
-    if let Some(task) = storage.find_task_by_id_mut(id) {
-        task.add_tag(tag.clone());
-        println!("🏷️ Tag '{}' agregado a la tarea {}", tag, id);
-    } else {
-        println!("❌ No se encontró una tarea con ID {}", id);
+// Manejar comando: start
+fn handle_start(app: &App, id: u64) {
+    let running = {
+        let storage = app.storage.lock().unwrap();
+        let Some(task) = storage.find_task_by_id(id) else {
+            println!("❌ No se encontró una tarea con ID {}", id);
+            return;
+        };
+        if task.is_completed() {
+            println!("❌ La tarea {} ya está completada", id);
+            return;
+        }
+        if task.is_tracking() {
+            println!("⏱️ Ya estás registrando tiempo en la tarea {}", id);
+            return;
+        }
+        storage.get_all_tasks().iter().find(|t| t.is_tracking()).map(|t| t.id)
+    };
+    
+    // Solo un cronómetro a la vez
+    if let Some(running) = running {
+        stop_timer(app, running);
+    }
+    
+    let mut title = String::new();
+    if modify_task(app, id, |task| {
+        task.start_timer(Utc::now());
+        title = task.title.clone();
+    }) {
+        println!("▶️ Registrando tiempo en [{}] {}", id, title);
     }
 }
 
-// Manejar comando: remove-tag
-fn handle_remove_tag(storage: &mut TaskStorage, id: u64, tag: String) {
-    if let Some(task) = storage.find_task_by_id_mut(id) {
-        if task.remove_tag(&tag) {
-            println!("🗑️ Tag '{}' removido de la tarea {}", tag, id);
-        } else {
-            println!("❌ La tarea {} no tiene el tag '{}'", id, tag);
+// Manejar comando: stop
+fn handle_stop(app: &App) {
+    let running = app.storage.lock().unwrap().get_all_tasks().iter().find(|t| t.is_tracking()).map(|t| t.id);
+    match running {
+        Some(id) => stop_timer(app, id),
+        None => println!("⏱️ No hay ningún cronómetro en marcha"),
+    }
+}
+
+fn stop_timer(app: &App, id: u64) {
+    let now = Utc::now();
+    let mut stopped = None;
+    
+    if modify_task(app, id, |task| stopped = task.stop_timer(now).map(|elapsed| (task.title.clone(), elapsed, task.tracked_time(now)))) {
+        if let Some((title, elapsed, total)) = stopped {
+            println!("⏹️ [{}] {}: {} (total {})", id, title,
+                     timesheet::format_duration(elapsed), timesheet::format_duration(total));
         }
-    } else {
-        println!("❌ No se encontró una tarea con ID {}", id);
     }
 }
 
-// Manejar comando: clear-tags
-fn handle_clear_tags(storage: &mut TaskStorage, id: u64) {
-    if let Some(task) = storage.find_task_by_id_mut(id) {
-        let tags_count = task.get_tags().len();
-        task.clear_tags();
-        println!("🧹 {} tags removidos de la tarea {}", tags_count, id);
-    } else {
+// Manejar comando: pomodoro <id>
+fn handle_pomodoro_start(storage: &TaskStorage, config: &Config, pomodoro: &mut Option<Pomodoro>, id: u64) {
+    let Some(task) = storage.find_task_by_id(id) else {
         println!("❌ No se encontró una tarea con ID {}", id);
+        return;
+    };
+    if task.is_completed() {
+        println!("❌ La tarea {} ya está completada", id);
+        return;
+    }
+    
+    if let Some(previous) = pomodoro.as_ref().filter(|p| p.task_id != id) {
+        println!("⏹️ Pomodoro de la tarea {} detenido", previous.task_id);
     }
+    
+    *pomodoro = Some(Pomodoro::start(id, config, Local::now()));
+    println!("🍅 Pomodoro de {} min en [{}] {}", config.pomodoro_minutes, id, task.title);
+    println!("   Descansos de {} min ({} min cada {} pomodoros). 'pomodoro --stop' para terminar",
+             config.short_break_minutes, config.long_break_minutes, config.long_break_every);
 }
 
-// Manejar comando: schedule
-fn handle_schedule(storage: &mut TaskStorage, id: u64, datetime: chrono::DateTime<chrono::Local>) {
-    if let Some(task) = storage.find_task_by_id_mut(id) {
-        task.schedule_for(datetime);
-        println!("⏰ Tarea {} programada para {}", id, datetime.format("%d/%m/%Y %H:%M"));
-    } else {
-        println!("❌ No se encontró una tarea con ID {}", id);
+// Manejar comando: pomodoro (sin ID)
+fn handle_pomodoro_status(storage: &TaskStorage, pomodoro: &Option<Pomodoro>) {
+    let Some(current) = pomodoro else {
+        println!("🍅 No hay ningún pomodoro en curso. Usa 'pomodoro <id>'");
+        return;
+    };
+    
+    let title = storage.find_task_by_id(current.task_id).map(|t| t.title.as_str()).unwrap_or("?");
+    println!("🍅 [{}] {}: {}, quedan {} ({} completados)", current.task_id, title, current.phase.name(),
+             timesheet::format_duration(current.remaining(Local::now())), current.completed);
+}
+
+// Manejar comando: pomodoro --stop
+fn handle_pomodoro_stop(pomodoro: &mut Option<Pomodoro>) {
+    match pomodoro.take() {
+        Some(stopped) => println!("⏹️ Pomodoro detenido ({} completados)", stopped.completed),
+        None => println!("🍅 No hay ningún pomodoro en curso"),
     }
 }
 
-// Manejar comando: snooze
-fn handle_snooze(storage: &mut TaskStorage, id: u64, minutes: i64) {
-    if storage.snooze_task(id, minutes) {
-        println!("⏸️ Tarea {} pospuesta por {} minutos", id, minutes);
-    } else {
-        println!("❌ No se encontró una tarea con ID {}", id);
+// Manejar comando: estimates
+fn handle_estimates(storage: &TaskStorage) {
+    let (groups, total) = estimates::accuracy_by_tag(storage.get_all_tasks(), Utc::now());
+    if total.tasks == 0 {
+        println!("📐 No hay tareas completadas con estimación y tiempo registrado");
+        return;
     }
+    
+    println!("📐 Estimado vs. real (tareas completadas):\n");
+    println!("   {:<20} {:>6} {:>10} {:>10} {:>8}", "", "Tareas", "Estimado", "Real", "Desvío");
+    let print_row = |accuracy: &estimates::Accuracy| {
+        println!("   {:<20} {:>6} {:>10} {:>10} {:>+7.0}%", accuracy.group, accuracy.tasks,
+                 timesheet::format_duration(accuracy.estimated), timesheet::format_duration(accuracy.actual), accuracy.deviation());
+    };
+    groups.iter().for_each(print_row);
+    println!();
+    print_row(&total);
+    println!("\n   Desvío positivo: se tardó más de lo estimado");
 }
 
-// Manejar comando: scheduled
-fn handle_scheduled(storage: &TaskStorage) {
-    let tasks: Vec<_> = storage.get_scheduled_tasks().collect();
+// Manejar comando: capacity
+fn handle_capacity(storage: &TaskStorage, config: &Config, days: u32) {
+    let available = estimates::available_per_day(config.available_hours_per_day);
+    let today = Local::now().date_naive();
     
-    if tasks.is_empty() {
-        println!("📅 No hay tareas programadas");
-    } else {
-        println!("📅 Tareas programadas:\n");
-        for task in tasks {
-            let status = if task.is_completed() { "✅" } else { "⏳" };
+    println!("📐 Trabajo programado ({} disponibles por día):\n", timesheet::format_duration(available));
+    for day in (0..days).filter_map(|offset| today.checked_add_days(chrono::Days::new(offset as u64))) {
+        let load = estimates::scheduled_load(storage.get_all_tasks(), day, Utc::now());
+        let warning = if !available.is_zero() && load > available { " ⚠️" } else { "" };
+        println!("   {} {}  {:>8}{}", weekday_name(day.weekday()), day.format("%d/%m/%Y"), timesheet::format_duration(load), warning);
+    }
+}
+
+// Avisa si el día en que está programada la tarea tiene más trabajo que horas disponibles
+fn warn_if_over_capacity(storage: &TaskStorage, config: &Config, id: u64) {
+    let available = estimates::available_per_day(config.available_hours_per_day);
+    let Some(day) = storage.find_task_by_id(id).and_then(|t| t.scheduled_for).map(|s| s.date_naive()) else { return };
+    
+    let load = estimates::scheduled_load(storage.get_all_tasks(), day, Utc::now());
+    if !available.is_zero() && load > available {
+        println!("⚠️ El {} hay {} de trabajo estimado y solo {} disponibles",
+                 day.format("%d/%m/%Y"), timesheet::format_duration(load), timesheet::format_duration(available));
+    }
+}
+
+// Manejar comando: timesheet
+fn handle_timesheet(storage: &TaskStorage, from: Option<NaiveDate>, to: Option<NaiveDate>,
+                    by: timesheet::TimesheetGroup, csv: Option<String>) {
+    let sheet = timesheet::build(storage.get_all_tasks(), from, to, by, Utc::now());
+    if sheet.rows.is_empty() {
+        println!("⏱️ No hay tiempo registrado en ese período");
+        return;
+    }
+    
+    if let Some(file) = csv {
+        let result = std::fs::File::create(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|f| timesheet::write_csv(&sheet, f));
+        match result {
+            Ok(()) => println!("📤 Hoja de horas exportada a {}", file),
+            Err(e) => println!("❌ Error al escribir {}: {}", file, e),
+        }
+        return;
+    }
+    
+    let format_day = |day: Option<NaiveDate>| day.map(|d| d.format("%d/%m/%Y").to_string()).unwrap_or_else(|| "…".to_string());
+    println!("⏱️ Hoja de horas ({} - {}):\n", format_day(from), format_day(to));
+    
+    let mut current = None;
+    for row in &sheet.rows {
+        if by != timesheet::TimesheetGroup::Task && current != Some(&row.group) {
+            let group_total: chrono::Duration = sheet.rows.iter().filter(|r| r.group == row.group).map(|r| r.duration).sum();
+            println!("   {:<44} {:>8}", row.group.label(), timesheet::format_duration(group_total));
+            current = Some(&row.group);
+        }
+        let task = format!("[{}] {}", row.task_id, row.title);
+        match by {
+            timesheet::TimesheetGroup::Task => println!("   {:<44} {:>8}", task, timesheet::format_duration(row.duration)),
+            _ => println!("      {:<41} {:>8}", task, timesheet::format_duration(row.duration)),
+        }
+    }
+    
+    println!("\n   Total: {}", timesheet::format_duration(sheet.total));
+}
+
+// Eventos del historial: con 'eventlog' se leen del propio registro
+fn history_events(config: &Config) -> Option<Vec<eventlog::Event>> {
+    let path = match config.storage_format {
+        StorageFormat::EventLog => tasks_file(config),
+        _ => HISTORY_FILE.to_string(),
+    };
+    
+    match history::read(&path) {
+        Ok(events) => Some(events),
+        Err(e) => {
+            println!("❌ Error al leer el historial de {}: {}", path, e);
+            None
+        }
+    }
+}
+
+// Manejar comando: show --history
+fn handle_task_history(config: &Config, task: &Task) {
+    let Some(events) = history_events(config) else { return };
+    let events: Vec<&eventlog::Event> = events.iter().filter(|e| history::belongs_to(e, task)).collect();
+    
+    println!("\n   📜 Historial:");
+    if events.is_empty() {
+        println!("      Sin cambios registrados");
+        return;
+    }
+    
+    for event in events {
+        let at = event.at.with_timezone(&Local).format("%d/%m/%Y %H:%M");
+        for change in history::describe(event) {
+            println!("      {}  {}", at, change);
+        }
+    }
+}
+
+// Manejar comando: log
+fn handle_log(storage: &TaskStorage, config: &Config, from: Option<NaiveDate>, to: Option<NaiveDate>) {
+    let Some(events) = history_events(config) else { return };
+    
+    // Título de cada tarea (por uuid) en el momento del cambio; las eliminadas ya no están en storage
+    let mut titles: HashMap<Uuid, String> = storage.get_all_tasks().iter().map(|t| (t.uuid, t.title.clone())).collect();
+    // Tarea que ocupa cada ID, para los 'updated' antiguos sin uuid
+    let mut uuids: HashMap<u64, Uuid> = storage.get_all_tasks().iter().map(|t| (t.id, t.uuid)).collect();
+    let mut shown = 0;
+    
+    for event in &events {
+        let Some(id) = history::task_id(event) else { continue };
+        let Some(uuid) = history::task_uuid(event).or_else(|| uuids.get(&id).copied()) else { continue };
+        match &event.kind {
+            eventlog::EventKind::Added { task } | eventlog::EventKind::Deleted { task } => {
+                titles.insert(uuid, task.title.clone());
+                uuids.insert(id, uuid);
+            },
+            eventlog::EventKind::Updated { changes, .. } => {
+                if let Some(title) = changes.get("title").and_then(|c| c.after.as_str()) {
+                    titles.insert(uuid, title.to_string());
+                }
+            },
+            eventlog::EventKind::Snapshot { .. } => {},
+        }
+        
+        let day = event.at.with_timezone(&Local).date_naive();
+        if from.is_some_and(|from| day < from) || to.is_some_and(|to| day > to) {
+            continue;
+        }
+        
+        if shown == 0 {
+            println!("📜 Historial de cambios:\n");
+        }
+        shown += 1;
+        
+        let title = titles.get(&uuid).map(|t| t.as_str()).unwrap_or("?");
+        println!("   {}  [{}] {}", event.at.with_timezone(&Local).format("%d/%m/%Y %H:%M"), id, title);
+        for change in history::describe(event) {
+            println!("      {}", change);
+        }
+    }
+    
+    if shown == 0 {
+        println!("📜 No hay cambios registrados en ese período");
+    }
+}
+
+// Manejar comando: update
+#[allow(clippy::too_many_arguments)]
+fn handle_update(app: &App, id: u64, title: Option<String>, description: Option<String>, tags: Vec<String>,
+                 priority: Option<Priority>, project: Option<String>, due: Option<chrono::DateTime<Local>>,
+                 estimate: Option<chrono::Duration>, clear: Vec<UpdateField>) {
+    if title.as_deref().is_some_and(|t| t.trim().is_empty()) {
+        println!("❌ El título no puede estar vacío");
+        return;
+    }
+
+    let updated = modify_task(app, id, |task| {
+        // Primero vaciar lo pedido con --clear, así un valor nuevo en el mismo comando gana
+        for field in &clear {
+            match field {
+                UpdateField::Description => task.description = None,
+                UpdateField::Tags => task.clear_tags(),
+                UpdateField::Priority => task.priority = None,
+                UpdateField::Project => task.project = None,
+                UpdateField::Due => task.due = None,
+                UpdateField::Estimate => task.estimate_minutes = None,
+            }
+        }
+
+        // Actualizar campos si se proporcionan
+        if let Some(new_title) = title {
+            task.title = new_title;
+        }
+        
+        // Una descripción vacía (-d "") la elimina
+        if let Some(description) = description {
+            task.description = Some(description).filter(|d| !d.is_empty());
+        }
+        
+        // Reemplazar tags si se proporcionan
+        if !tags.is_empty() {
+            task.clear_tags();
+            for tag in tags {
+                task.add_tag(tag);
+            }
+        }
+        
+        if priority.is_some() {
+            task.priority = priority;
+        }
+        
+        // Igual que la descripción: --project "" lo quita
+        if let Some(project) = project {
+            task.project = Some(project).filter(|p| !p.is_empty());
+        }
+        
+        if due.is_some() {
+            task.due = due;
+        }
+        
+        if let Some(estimate) = estimate {
+            task.estimate_minutes = Some(estimate.num_minutes());
+        }
+    });
+    
+    if updated {
+        println!("✅ Tarea {} actualizada exitosamente", id);
+    }
+}
+
+// Manejar comando: add-tag
+fn handle_add_tag(app: &App, id: u64, tag: String) {
+    if modify_task(app, id, |task| task.add_tag(tag.clone())) {
+        println!("🏷️ Tag '{}' agregado a la tarea {}", tag, id);
+    }
+}
+
+// Manejar comando: remove-tag
+fn handle_remove_tag(app: &App, id: u64, tag: String) {
+    let has_tag = app.storage.lock().unwrap().find_task_by_id(id).map(|task| task.has_tag(&tag));
+    if has_tag == Some(false) {
+        println!("❌ La tarea {} no tiene el tag '{}'", id, tag);
+    } else if modify_task(app, id, |task| { task.remove_tag(&tag); }) {
+        println!("🗑️ Tag '{}' removido de la tarea {}", tag, id);
+    }
+}
+
+// Manejar comando: clear-tags
+fn handle_clear_tags(app: &App, id: u64) {
+    let mut tags_count = 0;
+    
+    if modify_task(app, id, |task| {
+        tags_count = task.get_tags().len();
+        task.clear_tags();
+    }) {
+        println!("🧹 {} tags removidos de la tarea {}", tags_count, id);
+    }
+}
+
+// Manejar comando: schedule
+fn handle_schedule(app: &App, id: u64, datetime: chrono::DateTime<chrono::Local>) {
+    if modify_task(app, id, |task| task.schedule_for(datetime)) {
+        println!("⏰ Tarea {} programada para {}", id, datetime.format("%d/%m/%Y %H:%M"));
+    }
+}
+
+// Manejar comando: snooze
+fn handle_snooze(app: &App, id: u64, when: String) {
+    match snooze_task(app, id, &when) {
+        Ok(messages) => messages.iter().for_each(|m| println!("{}", m)),
+        Err(e) => println!("{}", e),
+    }
+}
+
+// Pospone la tarea y retorna los mensajes para el usuario
+// (pasa por los hooks on-modify, que pueden cambiarlo o impedirlo)
+fn snooze_task(app: &App, id: u64, when: &str) -> Result<Vec<String>, String> {
+    let (until, quiet_until) = {
+        let config = app.config.lock().unwrap();
+        let until = config.resolve_snooze(when, Local::now()).map_err(|e| format!("❌ {}", e))?;
+        (until, config.quiet_until(until))
+    };
+    
+    try_modify_task(app, id, |task| {
+        task.snooze(until);
+        // Si el nuevo recordatorio cae en horas de silencio, moverlo al final de la ventana
+        if let Some(quiet_until) = quiet_until {
+            task.defer_until(quiet_until);
+        }
+    })?;
+    
+    let mut messages = vec![format!("⏸️ Tarea {} pospuesta hasta el {}", id, until.format("%d/%m/%Y %H:%M"))];
+    if let Some(quiet_until) = quiet_until {
+        messages.push(format!("🌙 Cae en horas de silencio, se avisará el {}", quiet_until.format("%d/%m/%Y %H:%M")));
+    }
+    
+    let (storage, config) = lock(app);
+    if let Some(task) = storage.find_task_by_id(id).filter(|t| config.is_snooze_escalated(t.snooze_count)) {
+        messages.push(format!("🚨 Ya pospusiste esta tarea {} veces. Los próximos recordatorios serán insistentes", task.snooze_count));
+    }
+    Ok(messages)
+}
+
+// Manejar comando: snooze-presets
+fn handle_snooze_presets(config: &mut Config, action: SnoozePresetsAction) {
+    match action {
+        SnoozePresetsAction::List => {
+            if config.snooze_presets.is_empty() {
+                println!("⏸️ No hay presets de snooze");
+                return;
+            }
+            
+            println!("⏸️ Presets de snooze:\n");
+            for preset in &config.snooze_presets {
+                println!("   {} → {}", preset.name, preset.when);
+            }
+            println!("\n   🚨 Recordatorios insistentes después de {} snoozes", config.snooze_limit);
+        },
+        SnoozePresetsAction::Add { name, when } => {
+            // Validar la expresión antes de guardarla
+            if let Err(e) = cli::parse_datetime(&when) {
+                println!("❌ {}", e);
+                return;
+            }
+            
+            config.snooze_presets.retain(|p| p.name != name);
+            println!("⏸️ Preset '{}' guardado: {}", name, when);
+            config.snooze_presets.push(SnoozePreset { name, when });
+        },
+        SnoozePresetsAction::Remove { name } => {
+            let original_len = config.snooze_presets.len();
+            config.snooze_presets.retain(|p| p.name != name);
+            if config.snooze_presets.len() < original_len {
+                println!("🗑️ Preset '{}' eliminado", name);
+            } else {
+                println!("❌ No existe el preset '{}'", name);
+            }
+        },
+    }
+}
+
+// Manejar comando: scheduled
+fn handle_scheduled(storage: &TaskStorage) {
+    let tasks: Vec<_> = storage.get_scheduled_tasks().collect();
+    
+    if tasks.is_empty() {
+        println!("📅 No hay tareas programadas");
+    } else {
+        println!("📅 Tareas programadas:\n");
+        for task in tasks {
+            let status = if task.is_completed() { "✅" } else { "⏳" };
             print!("{} [{}] {}", status, task.id, task.title);
             
             if let Some(scheduled) = task.scheduled_for {
@@ -397,3 +1348,609 @@ fn handle_scheduled(storage: &TaskStorage) {
         }
     }
 }
+
+// Manejar comando: review. Las tareas solo se bloquean para aplicar cada respuesta,
+// así el scheduler sigue funcionando mientras se piensa
+fn handle_review(app: &App, weekly: bool) {
+    let recent = if weekly { chrono::Duration::weeks(1) } else { chrono::Duration::days(1) };
+    let candidates = {
+        let (storage, config) = lock(app);
+        review::candidates(storage.get_all_tasks(), &config, Utc::now(), recent)
+    };
+    
+    if candidates.is_empty() {
+        println!("🎉 No hay tareas para revisar");
+        return;
+    }
+    println!("🔎 {} tareas para revisar\n   {}", candidates.len(), review::HELP);
+    
+    let mut reviewed = 0;
+    for (i, (id, reasons)) in candidates.iter().enumerate() {
+        {
+            let storage = app.storage.lock().unwrap();
+            // Pudo completarse o eliminarse mientras tanto
+            let Some(task) = storage.find_task_by_id(*id).filter(|t| !t.is_completed()) else { continue };
+            
+            println!("\n[{}/{}] [{}] {}", i + 1, candidates.len(), task.id, task.title);
+            if !task.get_tags().is_empty() {
+                println!("   🏷️ {}", task.get_tags().join(", "));
+            }
+            if let Some(scheduled) = task.scheduled_for {
+                println!("   🕐 {}", scheduled.format("%d/%m/%Y %H:%M"));
+            }
+            println!("   {}", reasons.iter().map(|r| r.describe()).collect::<Vec<_>>().join(" · "));
+        }
+        
+        let action = loop {
+            print!("   ¿Qué hacer? ");
+            io::stdout().flush().unwrap();
+            
+            let mut input = String::new();
+            // Sin más entrada (ej: fin de un pipe) se termina la revisión
+            if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+                break review::Action::Quit;
+            }
+            match review::parse_action(&input) {
+                Ok(action) => break action,
+                Err(e) => println!("   ❌ {}\n   {}", e, review::HELP),
+            }
+        };
+        if action == review::Action::Quit {
+            break;
+        }
+        
+        match action {
+            review::Action::Complete => handle_complete(app, *id),
+            review::Action::Reschedule(datetime) => handle_schedule(app, *id, datetime),
+            review::Action::Snooze(when) => handle_snooze(app, *id, when),
+            review::Action::Retag(tags) => {
+                if modify_task(app, *id, |task| {
+                    task.clear_tags();
+                    tags.into_iter().for_each(|tag| task.add_tag(tag));
+                }) {
+                    println!("🏷️ Tags actualizados");
+                }
+            },
+            review::Action::Delete => handle_delete(app, *id),
+            review::Action::Skip | review::Action::Quit => {},
+        }
+        
+        if let Some(task) = app.storage.lock().unwrap().find_task_by_id_mut(*id) {
+            task.last_reviewed = Some(Utc::now());
+        }
+        reviewed += 1;
+    }
+    
+    println!("\n✅ Revisadas {} de {} tareas", reviewed, candidates.len());
+}
+
+// Manejar comando: agenda
+fn handle_agenda(storage: &TaskStorage, week: bool) {
+    let today = Local::now().date_naive();
+    let to = if week { agenda::end_of_week(today) } else { today };
+    let agenda = agenda::build(storage.get_all_tasks(), today, to);
+    
+    if !agenda.overdue.is_empty() {
+        println!("⌛ Atrasadas:");
+        for entry in &agenda.overdue {
+            println!("   {} {} [{}] {}", entry.at.format("%d/%m %H:%M"), entry.label(), entry.task_id, entry.title);
+        }
+        println!();
+    }
+    
+    for (i, (day, entries)) in agenda.days.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let suffix = if *day == today { " (hoy)" } else { "" };
+        println!("📅 {} {}{}", weekday_name(day.weekday()), day.format("%d/%m/%Y"), suffix);
+        
+        if entries.is_empty() {
+            println!("   Sin tareas");
+        }
+        for (hour, group) in agenda::by_hour(entries) {
+            println!("   {:02}h", hour);
+            for entry in group {
+                println!("      {} {} [{}] {}", entry.at.format("%H:%M"), entry.label(), entry.task_id, entry.title);
+            }
+        }
+    }
+}
+
+// Manejar comando: calendar
+fn handle_calendar(storage: &TaskStorage, month: Option<NaiveDate>) {
+    let today = Local::now().date_naive();
+    let month = month.unwrap_or(today);
+    let tasks = storage.get_all_tasks();
+    
+    let Some(lines) = agenda::month_grid(tasks, month.year(), month.month(), today) else { return };
+    for line in lines {
+        println!("   {}", line);
+    }
+    println!("\n   • recordatorio   ! vence   › hoy");
+    
+    let entries = agenda::month_entries(tasks, month.year(), month.month());
+    if !entries.is_empty() {
+        println!();
+        for entry in entries {
+            println!("   {} {} [{}] {}", entry.at.format("%d/%m %H:%M"), entry.label(), entry.task_id, entry.title);
+        }
+    }
+}
+
+// Manejar comando: dnd
+fn handle_dnd(config: &mut Config, action: DndAction) {
+    match action {
+        DndAction::On => {
+            config.dnd = true;
+            println!("🔕 No molestar activado. Usa 'dnd off' para desactivarlo");
+        },
+        DndAction::Off => {
+            config.dnd = false;
+            config.dnd_until = None;
+            println!("🔔 No molestar desactivado");
+        },
+        DndAction::Until { datetime } => {
+            config.dnd_until = Some(datetime);
+            println!("🔕 No molestar hasta el {}", datetime.format("%d/%m/%Y %H:%M"));
+        },
+        DndAction::Status => {
+            if config.dnd {
+                println!("🔕 No molestar activado");
+            } else if let Some(until) = config.dnd_until.filter(|u| *u > Local::now()) {
+                println!("🔕 No molestar hasta el {}", until.format("%d/%m/%Y %H:%M"));
+            } else {
+                println!("🔔 No molestar desactivado");
+            }
+            
+            if let Some(until) = config.quiet_until(Local::now()) {
+                println!("🌙 En horas de silencio hasta el {}", until.format("%d/%m/%Y %H:%M"));
+            }
+        },
+    }
+}
+
+// Manejar comando: quiet-hours
+fn handle_quiet_hours(config: &mut Config, action: QuietHoursAction) {
+    match action {
+        QuietHoursAction::List => {
+            if config.quiet_hours.is_empty() {
+                println!("🌙 No hay horas de silencio configuradas");
+                return;
+            }
+            
+            println!("🌙 Horas de silencio:\n");
+            for (i, window) in config.quiet_hours.iter().enumerate() {
+                println!("   {}. {}", i + 1, format_quiet_window(window));
+            }
+        },
+        QuietHoursAction::Add { days, start, end } => {
+            let window = QuietWindow { days, start, end };
+            println!("🌙 Horas de silencio agregadas: {}", format_quiet_window(&window));
+            config.quiet_hours.push(window);
+        },
+        QuietHoursAction::Remove { index } => {
+            if index == 0 || index > config.quiet_hours.len() {
+                println!("❌ No existe la ventana de silencio {}", index);
+            } else {
+                let window = config.quiet_hours.remove(index - 1);
+                println!("🗑️ Horas de silencio eliminadas: {}", format_quiet_window(&window));
+            }
+        },
+        QuietHoursAction::Clear => {
+            let count = config.quiet_hours.len();
+            config.quiet_hours.clear();
+            println!("🧹 {} ventanas de silencio eliminadas", count);
+        },
+    }
+}
+
+fn format_quiet_window(window: &QuietWindow) -> String {
+    let days: Vec<&str> = window.days.iter().map(|d| weekday_name(*d)).collect();
+    format!("{}: {} - {}", days.join(", "), window.start.format("%H:%M"), window.end.format("%H:%M"))
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "lun",
+        Weekday::Tue => "mar",
+        Weekday::Wed => "mié",
+        Weekday::Thu => "jue",
+        Weekday::Fri => "vie",
+        Weekday::Sat => "sáb",
+        Weekday::Sun => "dom",
+    }
+}
+
+// Manejar comando: webhook
+fn handle_webhook(config: &mut Config, outbox: &Arc<Mutex<Outbox>>, action: WebhookAction) {
+    match action {
+        WebhookAction::List => {
+            if config.webhooks.is_empty() {
+                println!("🌐 No hay webhooks configurados");
+                return;
+            }
+            
+            println!("🌐 Webhooks:\n");
+            for (i, webhook) in config.webhooks.iter().enumerate() {
+                let events: Vec<&str> = webhook.events.iter().map(|e| e.name()).collect();
+                println!("   {}. {} [{}]", i + 1, webhook.url, events.join(", "));
+                if let Some(ref template) = webhook.template {
+                    println!("      📄 {}", template);
+                }
+            }
+        },
+        WebhookAction::Add { url, event, template } => {
+            let events = if event.is_empty() { WebhookConfig::default_events() } else { event };
+            println!("🌐 Webhook agregado: {}", url);
+            config.webhooks.push(WebhookConfig { url, events, template });
+        },
+        WebhookAction::Remove { index } => {
+            if index == 0 || index > config.webhooks.len() {
+                println!("❌ No existe el webhook {}", index);
+            } else {
+                let webhook = config.webhooks.remove(index - 1);
+                println!("🗑️ Webhook eliminado: {}", webhook.url);
+            }
+        },
+        WebhookAction::Outbox => {
+            let outbox = outbox.lock().unwrap();
+            let deliveries = outbox.get_deliveries();
+            if deliveries.is_empty() {
+                println!("📭 No hay envíos pendientes");
+                return;
+            }
+            
+            println!("📬 Envíos pendientes:\n");
+            for delivery in deliveries {
+                println!("   {} - {} intentos, próximo: {}", delivery.url, delivery.attempts,
+                         delivery.next_attempt_at.with_timezone(&Local).format("%d/%m/%Y %H:%M:%S"));
+                if let Some(ref error) = delivery.last_error {
+                    println!("      ⚠️ {}", error);
+                }
+            }
+        },
+        WebhookAction::Retry => {
+            outbox.lock().unwrap().retry_now();
+            let outbox = Arc::clone(outbox);
+            std::thread::spawn(move || WebhookNotifier::flush(&outbox));
+            println!("🔁 Reintentando envíos pendientes");
+        },
+    }
+}
+
+// Manejar comando: email
+fn handle_email(storage: &TaskStorage, config: &mut Config, action: EmailAction) {
+    match action {
+        EmailAction::Show => {
+            match config.email {
+                Some(ref email) => {
+                    let security = match email.security {
+                        SmtpSecurity::Tls => "tls",
+                        SmtpSecurity::StartTls => "starttls",
+                        SmtpSecurity::None => "sin cifrado",
+                    };
+                    let events: Vec<&str> = email.events.iter().map(|e| e.name()).collect();
+                    
+                    println!("📧 Configuración de email:\n");
+                    println!("   🖥️ Servidor: {}:{} ({})", email.server, email.port, security);
+                    if let Some(ref username) = email.username {
+                        println!("   👤 Usuario: {}", username);
+                    }
+                    println!("   📤 De: {}", email.from);
+                    println!("   📥 Para: {}", email.to.join(", "));
+                    println!("   🔔 Eventos: {}", events.join(", "));
+                    if let Some(agenda_time) = email.agenda_time {
+                        println!("   📅 Agenda diaria: {}", agenda_time.format("%H:%M"));
+                    }
+                },
+                None => println!("📧 Los emails no están configurados. Usa 'email set'"),
+            }
+        },
+        EmailAction::Set { server, port, security, username, password, from, to, event, agenda } => {
+            let port = port.unwrap_or(match security {
+                SmtpSecurity::Tls => 465,
+                SmtpSecurity::StartTls => 587,
+                SmtpSecurity::None => 25,
+            });
+            let events = if event.is_empty() { EmailConfig::default_events() } else { event };
+            
+            config.email = Some(EmailConfig {
+                server,
+                port,
+                security,
+                username,
+                password,
+                from,
+                to,
+                events,
+                agenda_time: agenda,
+            });
+            println!("📧 Email configurado. Usa 'email test' para probarlo");
+        },
+        EmailAction::Disable => {
+            config.email = None;
+            println!("📧 Emails desactivados");
+        },
+        EmailAction::Test => {
+            let Some(ref email) = config.email else {
+                println!("❌ Los emails no están configurados. Usa 'email set'");
+                return;
+            };
+            
+            let task = Task::new(0, "Email de prueba de RusTask".to_string(), Some("Si lees esto, los recordatorios por email funcionan".to_string()));
+            let notification = Notification { event: TaskEvent::Reminder, task: &task, escalated: false };
+            let (subject, plain, html) = email::render_task_email(&notification);
+            
+            match email::send_email(email, &subject, plain, html) {
+                Ok(()) => println!("✅ Email de prueba enviado a {}", email.to.join(", ")),
+                Err(e) => println!("❌ Error al enviar email: {}", e),
+            }
+        },
+        EmailAction::Agenda => {
+            let Some(ref email) = config.email else {
+                println!("❌ Los emails no están configurados. Usa 'email set'");
+                return;
+            };
+            
+            let tasks: Vec<&Task> = storage.get_all_tasks().iter().collect();
+            let (subject, plain, html) = email::render_agenda(&tasks, Local::now().date_naive());
+            
+            match email::send_email(email, &subject, plain, html) {
+                Ok(()) => println!("✅ Agenda enviada a {}", email.to.join(", ")),
+                Err(e) => println!("❌ Error al enviar la agenda: {}", e),
+            }
+        },
+    }
+}
+
+// Manejar comando: export
+fn handle_export(storage: &TaskStorage, format: ExportFormat, output: Option<String>, group_by: Option<GroupBy>, template: Option<String>) {
+    let tasks = storage.get_all_tasks();
+    let mut buffer = Vec::new();
+    
+    let is_report = matches!(format, ExportFormat::Markdown | ExportFormat::Org);
+    if !is_report && (group_by.is_some() || template.is_some()) {
+        println!("❌ --group-by y --template solo se usan con markdown y org");
+        return;
+    }
+    
+    let template = match template.map(std::fs::read_to_string).transpose() {
+        Ok(template) => template,
+        Err(e) => {
+            println!("❌ Error al leer la plantilla: {}", e);
+            return;
+        }
+    };
+    
+    let group_by = group_by.unwrap_or_default();
+    let result = match format {
+        ExportFormat::Csv => formats::csv::export(tasks, &mut buffer),
+        ExportFormat::TodoTxt => formats::todotxt::export(tasks, &mut buffer),
+        ExportFormat::Ical => formats::ical::export(tasks, &mut buffer),
+        ExportFormat::Taskwarrior => formats::taskwarrior::export(tasks, &mut buffer),
+        ExportFormat::Markdown => formats::report::export(tasks, ReportStyle::Markdown, group_by, template.as_deref(), &mut buffer),
+        ExportFormat::Org => formats::report::export(tasks, ReportStyle::Org, group_by, template.as_deref(), &mut buffer),
+    };
+    
+    if let Err(e) = result {
+        println!("❌ Error al exportar: {}", e);
+        return;
+    }
+    
+    match output {
+        Some(path) => match std::fs::write(&path, &buffer) {
+            Ok(()) => println!("📤 {} tareas exportadas a {}", tasks.len(), path),
+            Err(e) => println!("❌ Error al escribir {}: {}", path, e),
+        },
+        None => print!("{}", String::from_utf8_lossy(&buffer)),
+    }
+}
+
+// Manejar comando: import
+fn handle_import(app: &App, format: ImportFormat, file: String, dry_run: bool, map: Vec<String>) {
+    let data = match std::fs::read(&file) {
+        Ok(data) => data,
+        Err(e) => {
+            println!("❌ Error al leer {}: {}", file, e);
+            return;
+        }
+    };
+    
+    // "Columna=campo"
+    let mut mapping = Vec::new();
+    for entry in map {
+        match entry.split_once('=') {
+            Some((column, field)) => mapping.push((column.trim().to_string(), field.trim().to_string())),
+            None => {
+                println!("❌ Mapeo inválido '{}'. Use: --map \"Columna=campo\"", entry);
+                return;
+            }
+        }
+    }
+    
+    let storage = app.storage.lock().unwrap();
+    let result = match format {
+        ImportFormat::Csv => formats::csv::import(data.as_slice(), &mapping, storage.get_all_tasks()),
+        ImportFormat::TodoTxt => Ok(formats::todotxt::import(&String::from_utf8_lossy(&data), storage.get_all_tasks())),
+        ImportFormat::Ical => formats::ical::import(&String::from_utf8_lossy(&data), storage.get_all_tasks()),
+        ImportFormat::Taskwarrior => formats::taskwarrior::import(&String::from_utf8_lossy(&data), storage.get_all_tasks()),
+    };
+    drop(storage);
+    
+    let plan = match result {
+        Ok(plan) => plan,
+        Err(e) => {
+            println!("❌ Error al importar {}: {}", file, e);
+            return;
+        }
+    };
+    
+    for error in &plan.errors {
+        println!("⚠️ {}", error);
+    }
+    for task in &plan.duplicates {
+        println!("⏭️ Duplicada: {}", task.title);
+    }
+    for skipped in &plan.skipped {
+        println!("⏭️ Omitida: {}", skipped);
+    }
+    
+    if dry_run {
+        println!("🔍 Vista previa (no se guardó nada):\n");
+        for task in &plan.new_tasks {
+            let status = if task.is_completed() { "✅" } else { "⏳" };
+            let tags_str = if task.tags.is_empty() { String::new() } else { format!(" [{}]", task.tags.join(", ")) };
+            println!("   ➕ {} {}{}", status, task.title, tags_str);
+        }
+        println!("\n📥 Se importarían {} tareas ({} duplicadas, {} errores)",
+                 plan.new_tasks.len(), plan.duplicates.len(), plan.errors.len());
+        return;
+    }
+    
+    let hooks = app_hooks(app);
+    let mut imported = 0;
+    for task in plan.new_tasks {
+        // Los hooks on-add también se aplican a las tareas importadas
+        let task = match hooks.run_before(HookEvent::Add, None, task) {
+            Ok(task) => task,
+            Err(e) => {
+                print_hook_veto(&e);
+                continue;
+            }
+        };
+        
+        let task = {
+            let mut storage = app.storage.lock().unwrap();
+            let id = storage.add_task(task);
+            storage.find_task_by_id(id).cloned()
+        };
+        if let Some(task) = task {
+            hooks.run_after(HookEvent::Add, &task);
+        }
+        imported += 1;
+    }
+    
+    println!("📥 {} tareas importadas desde {} ({} duplicadas, {} errores)",
+             imported, file, plan.duplicates.len(), plan.errors.len());
+}
+
+// Manejar comando: storage
+fn handle_storage(storage: &mut TaskStorage, config: &mut Config, action: StorageAction) {
+    match action {
+        StorageAction::Show => {
+            let format = match config.storage_format {
+                StorageFormat::Json => "JSON",
+                StorageFormat::TodoTxt => "todo.txt",
+                StorageFormat::EventLog => "registro de eventos",
+            };
+            println!("💾 Formato: {} ({})", format, tasks_file(config));
+        },
+        StorageAction::Use { format, file } => {
+            let mut todo_txt_file = config.todo_txt_file.clone();
+            let mut event_log_file = config.event_log_file.clone();
+            match format {
+                StorageFormat::Json => {},
+                StorageFormat::TodoTxt => todo_txt_file = file.unwrap_or(todo_txt_file),
+                StorageFormat::EventLog => event_log_file = file.unwrap_or(event_log_file),
+            }
+            let path = match format {
+                StorageFormat::Json => TASKS_FILE.to_string(),
+                StorageFormat::TodoTxt => todo_txt_file.clone(),
+                StorageFormat::EventLog => event_log_file.clone(),
+            };
+            
+            // Un archivo existente (ej: el todo.txt del equipo) se adopta tal cual;
+            // si no existe, se crea con las tareas actuales
+            if std::path::Path::new(&path).exists() {
+                let mut loaded = TaskStorage::new();
+                if let Err(e) = loaded.load(format, &path) {
+                    println!("❌ Error al cargar {}: {}", path, e);
+                    return;
+                }
+                *storage = loaded;
+                println!("📂 Usando {} ({} tareas)", path, storage.get_stats().total);
+            } else {
+                if let Err(e) = storage.save(format, &path) {
+                    println!("❌ Error al escribir {}: {}", path, e);
+                    return;
+                }
+                println!("💾 {} tareas copiadas a {}", storage.get_stats().total, path);
+            }
+            
+            config.storage_format = format;
+            config.todo_txt_file = todo_txt_file;
+            config.event_log_file = event_log_file;
+        },
+    }
+}
+
+// Manejar comando: compact
+fn handle_compact(storage: &mut TaskStorage, config: &Config) {
+    if config.storage_format != StorageFormat::EventLog {
+        println!("❌ 'compact' solo sirve con el registro de eventos. Usa 'storage use eventlog'");
+        return;
+    }
+    
+    // El historial se descarta; queda completo en una copia de seguridad
+    let path = tasks_file(config);
+    if config.backup_count > 0 {
+        match Backups::from_config(config).create(&path) {
+            Ok(backup) => println!("💾 Historial guardado en {}", backup.display()),
+            Err(e) => {
+                println!("❌ Error al crear la copia: {}", e);
+                return;
+            }
+        }
+    }
+    
+    match storage.compact_event_log(&path) {
+        Ok(events) => println!("🗜️ {} compactado: {} eventos → 1 snapshot", path, events),
+        Err(e) => println!("❌ Error al compactar {}: {}", path, e),
+    }
+}
+
+// Manejar comando: backup
+fn handle_backup(storage: &mut TaskStorage, config: &Config, action: BackupAction) {
+    let backups = Backups::from_config(config);
+    let path = tasks_file(config);
+    
+    match action {
+        BackupAction::List => {
+            let list = backups.list(&path);
+            if list.is_empty() {
+                println!("💾 No hay copias de seguridad de {}", path);
+                return;
+            }
+            
+            println!("💾 Copias de seguridad de {}:\n", path);
+            for (i, backup) in list.iter().enumerate() {
+                println!("   {}. {} ({} bytes)", i + 1, backup.created_at.format("%d/%m/%Y %H:%M:%S"), backup.size);
+            }
+        },
+        BackupAction::Create => {
+            match backups.create(&path) {
+                Ok(backup) => println!("💾 Copia creada: {}", backup.display()),
+                Err(e) => println!("❌ Error al crear la copia: {}", e),
+            }
+        },
+        BackupAction::Restore { number } => {
+            let list = backups.list(&path);
+            let Some(backup) = number.checked_sub(1).and_then(|i| list.get(i)) else {
+                println!("❌ No existe la copia {}. Usa 'backup list'", number);
+                return;
+            };
+            
+            let mut restored = TaskStorage::new();
+            if let Err(e) = restored.load(config.storage_format, &backup.path.to_string_lossy()) {
+                println!("❌ La copia no se puede leer: {}", e);
+                return;
+            }
+            
+            // Al guardar, el estado actual queda como una copia más
+            *storage = restored;
+            println!("♻️ Restauradas {} tareas desde la copia del {}",
+                     storage.get_stats().total, backup.created_at.format("%d/%m/%Y %H:%M:%S"));
+        },
+    }
+}
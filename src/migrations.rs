@@ -0,0 +1,118 @@
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+// Versión actual del archivo de tareas:
+//   {"version": N, "next_id": 7, "tasks": [...]}
+// Los archivos sin "version" (un array de tareas) son la versión 0.
+pub const CURRENT_VERSION: u64 = 2;
+
+// MIGRATIONS[n] convierte un archivo de la versión n a la n + 1. Trabajan sobre
+// el JSON para poder renombrar o completar campos antes de deserializar.
+const MIGRATIONS: [fn(Value) -> Result<Value, String>; CURRENT_VERSION as usize] = [
+    wrap_in_envelope,
+    add_uuids,
+];
+
+pub fn file_version(data: &Value) -> Result<u64, String> {
+    match data {
+        Value::Array(_) => Ok(0),
+        Value::Object(object) => object.get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| "falta el campo 'version'".to_string()),
+        _ => Err("formato de archivo desconocido".to_string()),
+    }
+}
+
+// Lleva el archivo a la versión actual aplicando las migraciones en orden
+pub fn migrate(mut data: Value) -> Result<Value, String> {
+    let version = file_version(&data)?;
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "el archivo es de la versión {} y esta versión de rustask solo entiende hasta la {}",
+            version, CURRENT_VERSION
+        ));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        data = migration(data).map_err(|e| format!("migración {} → {}: {}", from, from + 1, e))?;
+    }
+
+    Ok(data)
+}
+
+// 0 → 1: el array de tareas pasa a un sobre con versión y next_id
+fn wrap_in_envelope(data: Value) -> Result<Value, String> {
+    let tasks = match data {
+        Value::Array(tasks) => tasks,
+        _ => return Err("se esperaba un array de tareas".to_string()),
+    };
+
+    let max_id = tasks.iter().filter_map(|t| t.get("id").and_then(|id| id.as_u64())).max().unwrap_or(0);
+    Ok(json!({ "version": 1, "next_id": max_id + 1, "tasks": tasks }))
+}
+
+// 1 → 2: cada tarea recibe un UUID permanente. Las importadas de Taskwarrior
+// ya traían el suyo en extras, y se conserva.
+fn add_uuids(mut data: Value) -> Result<Value, String> {
+    let tasks = data.get_mut("tasks")
+        .and_then(|t| t.as_array_mut())
+        .ok_or("falta la lista 'tasks'")?;
+
+    for task in tasks.iter_mut() {
+        let task = task.as_object_mut().ok_or("una tarea no es un objeto")?;
+        if task.contains_key("uuid") {
+            continue;
+        }
+
+        let imported = task.get_mut("extras")
+            .and_then(|extras| extras.as_object_mut())
+            .and_then(|extras| extras.remove("uuid"))
+            .and_then(|uuid| uuid.as_str().and_then(|u| Uuid::parse_str(u).ok()));
+        let uuid = imported.unwrap_or_else(Uuid::new_v4);
+        task.insert("uuid".to_string(), json!(uuid));
+    }
+
+    data["version"] = json!(2);
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrates_legacy_array() {
+        let legacy = json!([{"id": 2, "title": "a"}, {"id": 5, "title": "b"}]);
+        assert_eq!(file_version(&legacy).unwrap(), 0);
+
+        let migrated = migrate(legacy).unwrap();
+        assert_eq!(migrated["version"], CURRENT_VERSION);
+        assert_eq!(migrated["next_id"], 6);
+        assert_eq!(migrated["tasks"].as_array().unwrap().len(), 2);
+
+        // Un archivo ya actualizado no cambia
+        assert_eq!(migrate(migrated.clone()).unwrap(), migrated);
+    }
+
+    #[test]
+    fn test_adds_uuids_keeping_imported_ones() {
+        let imported = "4f6c2a1e-8d7b-4c1e-9f3a-2b5d6e7f8a9b";
+        let v1 = json!({"version": 1, "next_id": 3, "tasks": [
+            {"id": 1, "title": "a"},
+            {"id": 2, "title": "b", "extras": {"uuid": imported, "estimate": "2h"}},
+        ]});
+
+        let migrated = migrate(v1).unwrap();
+        let tasks = migrated["tasks"].as_array().unwrap();
+        assert!(Uuid::parse_str(tasks[0]["uuid"].as_str().unwrap()).is_ok());
+        assert_eq!(tasks[1]["uuid"], imported);
+        assert_eq!(tasks[1]["extras"], json!({"estimate": "2h"}));
+    }
+
+    #[test]
+    fn test_rejects_newer_versions() {
+        let newer = json!({"version": CURRENT_VERSION + 1, "next_id": 1, "tasks": []});
+        assert!(migrate(newer).unwrap_err().contains("solo entiende hasta"));
+        assert!(migrate(json!("texto")).is_err());
+    }
+}
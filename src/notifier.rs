@@ -0,0 +1,285 @@
+use std::sync::{Arc, Mutex};
+use chrono::Local;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::task::Task;
+
+// Eventos de una tarea que pueden generar una notificación
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEvent {
+    Reminder,
+    Overdue,
+    Added,
+    Completed,
+    // Transiciones del modo pomodoro
+    PomodoroCompleted,
+    BreakEnded,
+}
+
+impl TaskEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaskEvent::Reminder => "reminder",
+            TaskEvent::Overdue => "overdue",
+            TaskEvent::Added => "added",
+            TaskEvent::Completed => "completed",
+            TaskEvent::PomodoroCompleted => "pomodoro_completed",
+            TaskEvent::BreakEnded => "break_ended",
+        }
+    }
+}
+
+pub struct Notification<'a> {
+    pub event: TaskEvent,
+    pub task: &'a Task,
+    // La tarea superó el límite de snoozes y se avisa de forma insistente
+    pub escalated: bool,
+}
+
+// Un canal de notificación (terminal, webhook...)
+pub trait Notifier: Send + Sync {
+    fn notify(&self, config: &Config, notification: &Notification);
+
+    // Trabajo periódico llamado desde el scheduler (ej: reintentar envíos)
+    fn tick(&self, _config: &Config) {}
+}
+
+// Reenvía cada notificación a todos los canales registrados
+pub struct Notifiers {
+    notifiers: Vec<Box<dyn Notifier>>,
+}
+
+impl Notifiers {
+    pub fn new(notifiers: Vec<Box<dyn Notifier>>) -> Self {
+        Self { notifiers }
+    }
+
+    pub fn notify(&self, config: &Config, event: TaskEvent, task: &Task) {
+        let notification = Notification {
+            event,
+            task,
+            escalated: config.is_snooze_escalated(task.snooze_count),
+        };
+
+        for notifier in &self.notifiers {
+            notifier.notify(config, &notification);
+        }
+    }
+
+    pub fn tick(&self, config: &Config) {
+        for notifier in &self.notifiers {
+            notifier.tick(config);
+        }
+    }
+}
+
+// Avisos de una línea para la TUI. Mientras está abierta se guardan aquí en vez
+// de imprimirse, para no romper la pantalla.
+#[derive(Clone, Default)]
+pub struct Banners(Arc<Mutex<Option<Vec<String>>>>);
+
+impl Banners {
+    pub fn capture(&self) {
+        *self.0.lock().unwrap() = Some(Vec::new());
+    }
+
+    pub fn release(&self) {
+        *self.0.lock().unwrap() = None;
+    }
+
+    // Los avisos guardados desde la última llamada
+    pub fn take(&self) -> Vec<String> {
+        self.0.lock().unwrap().as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    // false si no se están capturando
+    fn push(&self, banner: String) -> bool {
+        match self.0.lock().unwrap().as_mut() {
+            Some(banners) => {
+                banners.push(banner);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Notificaciones en la terminal (y nativas en macOS)
+pub struct TerminalNotifier {
+    banners: Banners,
+}
+
+impl Notifier for TerminalNotifier {
+    fn notify(&self, _config: &Config, notification: &Notification) {
+        if let Some(banner) = Self::banner(notification) {
+            if self.banners.push(banner) {
+                return;
+            }
+        }
+
+        // Alta y completado ya se informan en la salida del comando
+        match notification.event {
+            TaskEvent::Reminder => Self::print_reminder(notification.task, notification.escalated),
+            TaskEvent::Overdue => Self::print_overdue(notification.task),
+            TaskEvent::PomodoroCompleted => Self::print_pomodoro_completed(notification.task),
+            TaskEvent::BreakEnded => println!("\n⏰ Fin del descanso: vuelve a '{}'\n", notification.task.title),
+            TaskEvent::Added | TaskEvent::Completed => {}
+        }
+    }
+}
+
+impl TerminalNotifier {
+    pub fn new(banners: Banners) -> Self {
+        Self { banners }
+    }
+
+    fn banner(notification: &Notification) -> Option<String> {
+        let task = notification.task;
+        match notification.event {
+            TaskEvent::Reminder if notification.escalated => {
+                Some(format!("🚨 Recordatorio insistente: [{}] {} (pospuesta {} veces)", task.id, task.title, task.snooze_count))
+            }
+            TaskEvent::Reminder => Some(format!("🔔 Recordatorio: [{}] {}", task.id, task.title)),
+            TaskEvent::Overdue => Some(format!("⌛ Atrasada: [{}] {}", task.id, task.title)),
+            TaskEvent::PomodoroCompleted => Some(format!("🍅 Pomodoro completado: '{}'. Toma un descanso", task.title)),
+            TaskEvent::BreakEnded => Some(format!("⏰ Fin del descanso: vuelve a '{}'", task.title)),
+            TaskEvent::Added | TaskEvent::Completed => None,
+        }
+    }
+
+    fn print_reminder(task: &Task, escalated: bool) {
+        println!("\n🔔 ═══════════════════════════════════");
+        if escalated {
+            println!("   🚨 RECORDATORIO INSISTENTE (pospuesta {} veces)", task.snooze_count);
+        } else {
+            println!("   ⏰ RECORDATORIO");
+        }
+        println!("   📝 Tarea: {}", task.title);
+        
+        if let Some(desc) = &task.description {
+            println!("   📄 {}", desc);
+        }
+        
+        if let Some(scheduled) = task.scheduled_for {
+            println!("   🕐 Programada: {}", scheduled.format("%d/%m/%Y %H:%M"));
+        }
+        
+        if !task.tags.is_empty() {
+            println!("   🏷️  Tags: {}", task.tags.join(", "));
+        }
+        
+        println!("   💡 Usa 'snooze {}' para posponer (o 'snooze {} tonight')", task.id, task.id);
+        println!("═══════════════════════════════════\n");
+        
+        // Enviar notificación del sistema en macOS
+        #[cfg(target_os = "macos")]
+        {
+            Self::send_macos_notification(task, escalated);
+        }
+    }
+    
+    // Envía notificaciones nativas de macOS usando terminal-notifier
+    #[cfg(target_os = "macos")]
+    fn send_macos_notification(task: &Task, escalated: bool) {
+        use std::process::Command;
+        
+        // Construir el mensaje de la notificacion
+        let mut message = task.title.clone();
+        if let Some(desc) = &task.description {
+            message.push_str("\n");
+            message.push_str(desc);
+        }
+        
+        // Los recordatorios insistentes usan otro subtítulo y sonido
+        let (subtitle, sound) = if escalated {
+            ("🚨 Recordatorio insistente", "Sosumi")
+        } else {
+            ("Recordatorio de Tarea", "Glass")
+        };
+        
+        // Ejecutar terminal-notifier para mostrar la notificacion
+        let result = Command::new("terminal-notifier")
+            .args(&[
+                "-title", "🦀 RusTask",
+                "-subtitle", subtitle,
+                "-message", &message,
+                "-sound", sound,
+                "-sender", "com.apple.Terminal",
+            ])
+            .output();
+        
+        // Verificar si la notificacion se envió correctamente
+        match result {
+            Ok(output) if output.status.success() => {
+                println!("✅ Notificación enviada");
+            }
+            Ok(output) => {
+                eprintln!("⚠️ Error al enviar notificación: {}", 
+                         String::from_utf8_lossy(&output.stderr));
+            }
+            Err(e) => {
+                eprintln!("⚠️ terminal-notifier no disponible: {}", e);
+                eprintln!("   Instala con: brew install terminal-notifier");
+            }
+        }
+    }
+
+    fn print_overdue(task: &Task) {
+        println!("\n⚠️ ═══════════════════════════════════");
+        println!("   ⌛ TAREA ATRASADA");
+        println!("   📝 Tarea: {}", task.title);
+        
+        if let Some(scheduled) = task.scheduled_for {
+            println!("   🕐 Programada: {}", scheduled.format("%d/%m/%Y %H:%M"));
+        }
+        
+        println!("   💡 Usa 'complete {}' o 'schedule {} <fecha>'", task.id, task.id);
+        println!("═══════════════════════════════════\n");
+    }
+
+    fn print_pomodoro_completed(task: &Task) {
+        let today = task.pomodoros.iter()
+            .filter(|p| p.with_timezone(&Local).date_naive() == Local::now().date_naive())
+            .count();
+        println!("\n🍅 Pomodoro completado: '{}' ({} hoy). Toma un descanso\n", task.title, today);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(target_os = "macos")]
+    fn test_macos_notification() {
+        use std::process::Command;
+        
+        println!("🧪 Probando notificación de macOS...");
+        
+        let result = Command::new("terminal-notifier")
+            .args(&[
+                "-title", "RusTask Test",
+                "-message", "Esta es una notificación de prueba",
+                "-sound", "Glass",
+                "-sender", "com.apple.Terminal",
+            ])
+            .output();
+        
+        match result {
+            Ok(output) if output.status.success() => {
+                println!("✅ Notificación enviada exitosamente");
+                println!("   Deberías ver una notificación en la esquina superior derecha");
+            }
+            Ok(output) => {
+                println!("❌ Error al enviar notificación");
+                println!("   stderr: {}", String::from_utf8_lossy(&output.stderr));
+                panic!("La notificación falló");
+            }
+            Err(e) => {
+                println!("❌ Error ejecutando terminal-notifier: {}", e);
+                println!("   Instala con: brew install terminal-notifier");
+                panic!("No se pudo ejecutar terminal-notifier");
+            }
+        }
+    }
+}
\ No newline at end of file
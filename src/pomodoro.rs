@@ -0,0 +1,112 @@
+use chrono::{DateTime, Duration, Local};
+use crate::config::Config;
+
+// Ciclos de foco y descanso sobre una tarea. El scheduler revisa cada segundo
+// si terminó la fase actual y avisa por los notificadores.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Focus,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Focus => "foco",
+            Phase::ShortBreak => "descanso corto",
+            Phase::LongBreak => "descanso largo",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    // Terminó un pomodoro y empieza un descanso
+    FocusEnded,
+    // Terminó el descanso y empieza otro pomodoro
+    BreakEnded,
+}
+
+#[derive(Debug)]
+pub struct Pomodoro {
+    pub task_id: u64,
+    pub phase: Phase,
+    pub ends_at: DateTime<Local>,
+    // Pomodoros completados en esta sesión (decide cuándo toca el descanso largo)
+    pub completed: u32,
+}
+
+impl Pomodoro {
+    pub fn start(task_id: u64, config: &Config, now: DateTime<Local>) -> Self {
+        Self {
+            task_id,
+            phase: Phase::Focus,
+            ends_at: now + Duration::minutes(config.pomodoro_minutes),
+            completed: 0,
+        }
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        now >= self.ends_at
+    }
+
+    pub fn remaining(&self, now: DateTime<Local>) -> Duration {
+        (self.ends_at - now).max(Duration::zero())
+    }
+
+    // Pasa a la fase siguiente, que empieza en `now`
+    pub fn advance(&mut self, config: &Config, now: DateTime<Local>) -> Transition {
+        match self.phase {
+            Phase::Focus => {
+                self.completed += 1;
+                let long = config.long_break_every > 0 && self.completed.is_multiple_of(config.long_break_every);
+                let (phase, minutes) = if long {
+                    (Phase::LongBreak, config.long_break_minutes)
+                } else {
+                    (Phase::ShortBreak, config.short_break_minutes)
+                };
+                self.phase = phase;
+                self.ends_at = now + Duration::minutes(minutes);
+                Transition::FocusEnded
+            }
+            Phase::ShortBreak | Phase::LongBreak => {
+                self.phase = Phase::Focus;
+                self.ends_at = now + Duration::minutes(config.pomodoro_minutes);
+                Transition::BreakEnded
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycles_with_long_break() {
+        let config = Config { long_break_every: 2, ..Config::default() };
+        let start = Local::now();
+        let mut pomodoro = Pomodoro::start(7, &config, start);
+        assert!(!pomodoro.is_due(start + Duration::minutes(24)));
+
+        let mut now = pomodoro.ends_at;
+        let mut phases = Vec::new();
+        for _ in 0..4 {
+            assert!(pomodoro.is_due(now));
+            let transition = pomodoro.advance(&config, now);
+            phases.push((transition, pomodoro.phase));
+            now = pomodoro.ends_at;
+        }
+
+        assert_eq!(phases, vec![
+            (Transition::FocusEnded, Phase::ShortBreak),
+            (Transition::BreakEnded, Phase::Focus),
+            (Transition::FocusEnded, Phase::LongBreak),
+            (Transition::BreakEnded, Phase::Focus),
+        ]);
+        assert_eq!(pomodoro.completed, 2);
+        assert_eq!(now - start, Duration::minutes(25 + 5 + 25 + 15 + 25));
+    }
+}
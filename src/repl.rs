@@ -0,0 +1,218 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use clap::{Arg, Command, CommandFactory};
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use crate::cli::{self, Cli};
+use crate::storage::TaskStorage;
+use crate::task::Task;
+
+// Autocompletado del modo interactivo: subcomandos, flags y valores posibles
+// salen de la definición de clap; los tags y los IDs, de las tareas actuales.
+pub struct ReplHelper {
+    storage: Arc<Mutex<TaskStorage>>,
+    command: Command,
+}
+
+impl ReplHelper {
+    pub fn new(storage: Arc<Mutex<TaskStorage>>) -> Self {
+        Self { storage, command: Cli::command() }
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let storage = self.storage.lock().unwrap();
+        Ok(complete(&self.command, storage.get_all_tasks(), &line[..pos]))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+// Candidatos para la última palabra de `line` (lo escrito hasta el cursor) y
+// la posición donde empieza esa palabra. Las palabras se separan igual que al
+// ejecutar el comando (cli::parse_args), así que las comillas agrupan.
+pub fn complete(root: &Command, tasks: &[Task], line: &str) -> (usize, Vec<Pair>) {
+    let start = last_word_start(line);
+    let word = unquote(&line[start..]);
+    let Ok(previous) = cli::parse_args(&line[..start]) else { return (start, Vec::new()) };
+    let previous: Vec<&str> = previous.iter().map(String::as_str).collect();
+
+    // Bajar por los subcomandos ya escritos (ej: "storage use")
+    let mut command = root;
+    let mut rest = previous.as_slice();
+    while let Some((first, tail)) = rest.split_first() {
+        let Some(subcommand) = command.find_subcommand(first) else { break };
+        command = subcommand;
+        rest = tail;
+    }
+
+    let candidates = if word.starts_with('-') {
+        flags(command)
+    } else if let Some(arg) = rest.last().and_then(|previous| flag_with_value(command, previous)) {
+        values(arg, tasks)
+    } else if command.has_subcommands() {
+        let mut names: Vec<Pair> = command.get_subcommands().map(|c| pair(c.get_name())).collect();
+        if previous.is_empty() {
+            names.extend(["exit", "quit"].map(pair));
+        }
+        names
+    } else {
+        positional(command, rest).map(|arg| values(arg, tasks)).unwrap_or_default()
+    };
+
+    // Las tareas también se encuentran por cualquier parte del título
+    let word = word.to_lowercase();
+    let matches = candidates.into_iter()
+        .filter(|c| c.replacement.to_lowercase().starts_with(&word)
+            || (c.display != c.replacement && c.display.to_lowercase().contains(&word)))
+        .collect();
+    (start, matches)
+}
+
+// Después del último espacio que no está entre comillas ni escapado
+fn last_word_start(line: &str) -> usize {
+    let mut start = 0;
+    let mut quote = None;
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (None | Some('"'), '\\') => { chars.next(); },
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, c) if c.is_whitespace() => start = i + c.len_utf8(),
+            _ => {},
+        }
+    }
+    start
+}
+
+// La palabra que se está escribiendo, sin comillas (la última puede no estar cerrada todavía)
+fn unquote(word: &str) -> String {
+    ["", "\"", "'"].iter()
+        .find_map(|close| cli::parse_args(&format!("{}{}", word, close)).ok())
+        .and_then(|args| args.into_iter().next())
+        .unwrap_or_default()
+}
+
+fn pair(text: &str) -> Pair {
+    Pair { display: text.to_string(), replacement: text.to_string() }
+}
+
+fn flags(command: &Command) -> Vec<Pair> {
+    command.get_arguments()
+        .filter_map(|arg| arg.get_long())
+        .map(|long| pair(&format!("--{}", long)))
+        .chain(std::iter::once(pair("--help")))
+        .collect()
+}
+
+// El argumento de un flag que espera un valor ("--tag", "-t")
+fn flag_with_value<'a>(command: &'a Command, token: &str) -> Option<&'a Arg> {
+    command.get_arguments()
+        .filter(|arg| arg.get_action().takes_values())
+        .find(|arg| match token.strip_prefix("--") {
+            Some(long) => arg.get_long() == Some(long),
+            None => token.strip_prefix('-').and_then(|s| s.parse::<char>().ok()).is_some_and(|short| arg.get_short() == Some(short)),
+        })
+}
+
+// El argumento posicional que toca después de `previous`
+fn positional<'a>(command: &'a Command, previous: &[&str]) -> Option<&'a Arg> {
+    let mut count = 0;
+    let mut tokens = previous.iter();
+    while let Some(token) = tokens.next() {
+        if token.starts_with('-') {
+            // Saltar también el valor del flag
+            if flag_with_value(command, token).is_some() {
+                tokens.next();
+            }
+        } else {
+            count += 1;
+        }
+    }
+    command.get_positionals().nth(count)
+}
+
+// Valores para un argumento: los posibles de clap, tags existentes o IDs de tareas
+fn values(arg: &Arg, tasks: &[Task]) -> Vec<Pair> {
+    let possible = arg.get_possible_values();
+    if !possible.is_empty() {
+        return possible.iter().map(|v| pair(v.get_name())).collect();
+    }
+
+    match arg.get_id().as_str() {
+        "tag" | "tags" => {
+            let tags: BTreeSet<&str> = tasks.iter().flat_map(|t| t.tags.iter().map(String::as_str)).collect();
+            tags.into_iter().map(pair).collect()
+        },
+        // Se puede buscar por el título, pero se completa el ID
+        "id" => tasks.iter()
+            .map(|t| Pair { display: format!("{}  {}", t.id, t.title), replacement: t.id.to_string() })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            Task::new_with_tags(1, "Comprar pan".to_string(), None, vec!["casa".to_string(), "compras".to_string()]),
+            Task::new_with_tags(12, "Llamar a Ana".to_string(), None, vec!["trabajo".to_string()]),
+        ]
+    }
+
+    fn replacements(line: &str) -> (usize, Vec<String>) {
+        let (start, pairs) = complete(&Cli::command(), &tasks(), line);
+        (start, pairs.into_iter().map(|p| p.replacement).collect())
+    }
+
+    #[test]
+    fn test_completes_commands_and_flags() {
+        let (start, names) = replacements("sch");
+        assert_eq!((start, names), (0, vec!["schedule".to_string(), "scheduled".to_string()]));
+        assert!(replacements("e").1.contains(&"exit".to_string()));
+
+        // Subcomandos anidados y valores posibles de clap
+        assert_eq!(replacements("storage u").1, vec!["use"]);
+        assert_eq!(replacements("storage use t").1, vec!["todotxt"]);
+        assert_eq!(replacements("timesheet --by ta").1, vec!["tag", "task"]);
+
+        let (start, flags) = replacements("list --t");
+        assert_eq!(start, 5);
+        assert_eq!(flags, vec!["--tag"]);
+    }
+
+    #[test]
+    fn test_completes_tags_and_task_ids() {
+        assert_eq!(replacements("list --tag c").1, vec!["casa", "compras"]);
+        assert_eq!(replacements("add \"Pan\" -t tr").1, vec!["trabajo"]);
+
+        // Por ID o por el título
+        assert_eq!(replacements("complete 1").1, vec!["1", "12"]);
+        assert_eq!(replacements("complete ana").1, vec!["12"]);
+        // El segundo posicional de 'add-tag' es el tag
+        assert_eq!(replacements("add-tag 1 co").1, vec!["compras"]);
+
+        // Las comillas agrupan palabras y la última puede estar sin cerrar
+        assert_eq!(replacements("add-tag \"1\" \"co"), (12, vec!["compras".to_string()]));
+        assert_eq!(replacements("list --tag 'c").1, vec!["casa", "compras"]);
+        assert!(replacements("add \"Pan -t tr").1.is_empty());
+    }
+}
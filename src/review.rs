@@ -0,0 +1,137 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use crate::cli;
+use crate::config::Config;
+use crate::task::Task;
+
+// Revisión guiada: qué tareas conviene mirar y qué hacer con cada una
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reason {
+    // Pendiente desde hace N días sin revisar
+    Stale(i64),
+    // Sin hora programada ni fecha límite, y nunca revisada
+    Unscheduled,
+    // Pospuesta más veces que 'snooze_limit'
+    Snoozed(u32),
+}
+
+impl Reason {
+    pub fn describe(&self) -> String {
+        match self {
+            Reason::Stale(days) => format!("🕸️ pendiente hace {} días", days),
+            Reason::Unscheduled => "📭 sin programar".to_string(),
+            Reason::Snoozed(count) => format!("😴 pospuesta {} veces", count),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Complete,
+    Reschedule(DateTime<Local>),
+    // Expresión de snooze o nombre de un preset
+    Snooze(String),
+    Retag(Vec<String>),
+    Delete,
+    Skip,
+    Quit,
+}
+
+pub const HELP: &str = "[c]ompletar · [r] <fecha> reprogramar · [s] <cuándo> posponer · [t] <tags> cambiar tags · [d] eliminar · [n] saltar · [q] salir";
+
+// Tareas pendientes a revisar con sus motivos, las que tienen más motivos
+// primero. Se saltan las revisadas en el último `recent` (un día o una semana).
+pub fn candidates(tasks: &[Task], config: &Config, now: DateTime<Utc>, recent: Duration) -> Vec<(u64, Vec<Reason>)> {
+    let stale_after = Duration::days(config.review_stale_days);
+
+    let mut candidates: Vec<(u64, Vec<Reason>)> = tasks.iter()
+        .filter(|t| !t.is_completed())
+        .filter(|t| t.last_reviewed.is_none_or(|reviewed| now - reviewed >= recent))
+        .map(|task| {
+            let mut reasons = Vec::new();
+            let age = now - task.created_at;
+            if age >= stale_after && task.last_reviewed.is_none_or(|reviewed| now - reviewed >= stale_after) {
+                reasons.push(Reason::Stale(age.num_days()));
+            }
+            if task.scheduled_for.is_none() && task.due.is_none() && task.last_reviewed.is_none() {
+                reasons.push(Reason::Unscheduled);
+            }
+            if config.is_snooze_escalated(task.snooze_count) {
+                reasons.push(Reason::Snoozed(task.snooze_count));
+            }
+            (task.id, reasons)
+        })
+        .filter(|(_, reasons)| !reasons.is_empty())
+        .collect();
+
+    candidates.sort_by_key(|(id, reasons)| (std::cmp::Reverse(reasons.len()), *id));
+    candidates
+}
+
+// Respuesta a la pregunta de 'review', ej: "r mañana 10:00", "t casa, urgente"
+pub fn parse_action(input: &str) -> Result<Action, String> {
+    let input = input.trim();
+    let (command, rest) = match input.split_once(' ') {
+        Some((command, rest)) => (command, rest.trim()),
+        None => (input, ""),
+    };
+
+    match command.to_lowercase().as_str() {
+        "c" | "completar" => Ok(Action::Complete),
+        "r" | "reprogramar" if rest.is_empty() => Err("Falta la fecha, ej: r mañana 10:00".to_string()),
+        "r" | "reprogramar" => cli::parse_datetime(rest).map(Action::Reschedule),
+        "s" | "posponer" if rest.is_empty() => Err("Falta cuándo, ej: s +2h o s tomorrow-morning".to_string()),
+        "s" | "posponer" => Ok(Action::Snooze(rest.to_string())),
+        "t" | "tags" => Ok(Action::Retag(
+            rest.split([',', ' ']).filter(|t| !t.is_empty()).map(|t| t.to_string()).collect(),
+        )),
+        "d" | "eliminar" => Ok(Action::Delete),
+        "" | "n" | "saltar" => Ok(Action::Skip),
+        "q" | "salir" => Ok(Action::Quit),
+        other => Err(format!("Acción desconocida: '{}'", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates() {
+        let now = Utc::now();
+        let config = Config::default();
+        let mut tasks: Vec<Task> = (1..=5).map(|id| Task::new(id, format!("Tarea {}", id), None)).collect();
+        // Vieja y sin programar
+        tasks[0].created_at = now - Duration::days(20);
+        // Programada y pospuesta muchas veces
+        tasks[1].schedule_for(Local::now());
+        tasks[1].snooze_count = 4;
+        // Revisada ayer: con la revisión semanal no aparece
+        tasks[2].created_at = now - Duration::days(30);
+        tasks[2].last_reviewed = Some(now - Duration::days(1));
+        // Programada y reciente: nada que revisar
+        tasks[3].schedule_for(Local::now());
+        tasks[4].complete();
+
+        assert_eq!(candidates(&tasks, &config, now, Duration::weeks(1)), vec![
+            (1, vec![Reason::Stale(20), Reason::Unscheduled]),
+            (2, vec![Reason::Snoozed(4)]),
+        ]);
+
+        // En la diaria vuelve a salir, pero ya no cuenta como estancada ni sin programar
+        tasks[2].snooze_count = 5;
+        let daily = candidates(&tasks, &config, now + Duration::days(1), Duration::days(1));
+        assert_eq!(daily.iter().find(|(id, _)| *id == 3), Some(&(3, vec![Reason::Snoozed(5)])));
+    }
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!(parse_action("c"), Ok(Action::Complete));
+        assert_eq!(parse_action(""), Ok(Action::Skip));
+        assert_eq!(parse_action("s tomorrow-morning"), Ok(Action::Snooze("tomorrow-morning".to_string())));
+        assert_eq!(parse_action("t casa, urgente"), Ok(Action::Retag(vec!["casa".to_string(), "urgente".to_string()])));
+        assert!(matches!(parse_action("r 01/07/2025 10:00"), Ok(Action::Reschedule(_))));
+        assert!(parse_action("r").is_err());
+        assert!(parse_action("x").is_err());
+    }
+}
@@ -1,17 +1,33 @@
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
+use chrono::Local;
+use crate::config::Config;
+use crate::email;
+use crate::task::Task;
+use crate::notifier::{Notifiers, TaskEvent};
+use crate::pomodoro::{Pomodoro, Transition};
 use crate::storage::TaskStorage;
 
+// Cada cuántos ticks de un segundo se revisan los recordatorios
+const CHECK_EVERY_TICKS: u32 = 30;
+
 pub struct Scheduler {
     storage: Arc<Mutex<TaskStorage>>,
+    config: Arc<Mutex<Config>>,
+    notifiers: Arc<Notifiers>,
+    pomodoro: Arc<Mutex<Option<Pomodoro>>>,
     running: Arc<Mutex<bool>>,
 }
 
 impl Scheduler {
-    pub fn new(storage: Arc<Mutex<TaskStorage>>) -> Self {
+    pub fn new(storage: Arc<Mutex<TaskStorage>>, config: Arc<Mutex<Config>>, notifiers: Arc<Notifiers>,
+               pomodoro: Arc<Mutex<Option<Pomodoro>>>) -> Self {
         Self {
             storage,
+            config,
+            notifiers,
+            pomodoro,
             running: Arc::new(Mutex::new(false)),
         }
     }
@@ -26,11 +42,15 @@ impl Scheduler {
         drop(running);
 
         let storage = Arc::clone(&self.storage);
+        let config = Arc::clone(&self.config);
+        let notifiers = Arc::clone(&self.notifiers);
+        let pomodoro = Arc::clone(&self.pomodoro);
         let running = Arc::clone(&self.running);
 
         thread::spawn(move || {
             println!("🚀 Scheduler iniciado");
             
+            let mut ticks = 0;
             loop {
                 {
                     let is_running = running.lock().unwrap();
@@ -39,9 +59,22 @@ impl Scheduler {
                     }
                 }
 
+                // El pomodoro necesita precisión de segundos
+                Self::check_pomodoro(&pomodoro, &storage, &config, &notifiers);
+
                 // Revisar tareas pendientes cada 30 segundos
-                Self::check_due_tasks(&storage);
-                thread::sleep(Duration::from_secs(30));
+                if ticks % CHECK_EVERY_TICKS == 0 {
+                    Self::check_due_tasks(&storage, &config, &notifiers);
+                    
+                    Self::check_daily_agenda(&storage, &config);
+                    
+                    // Trabajo de los notificadores (reintentos de webhooks) sin bloquear las tareas
+                    let config_snapshot = config.lock().unwrap().clone();
+                    notifiers.tick(&config_snapshot);
+                }
+                ticks = ticks.wrapping_add(1);
+                
+                thread::sleep(Duration::from_secs(1));
             }
             
             println!("🛑 Scheduler detenido");
@@ -54,122 +87,106 @@ impl Scheduler {
         println!("⏸️ Deteniendo scheduler...");
     }
 
-    fn check_due_tasks(storage: &Arc<Mutex<TaskStorage>>) {
+    // Avanza el pomodoro en curso si terminó la fase y avisa del cambio
+    fn check_pomodoro(pomodoro: &Arc<Mutex<Option<Pomodoro>>>, storage: &Arc<Mutex<TaskStorage>>,
+                      config: &Arc<Mutex<Config>>, notifiers: &Notifiers) {
+        let now = Local::now();
+        if !pomodoro.lock().unwrap().as_ref().is_some_and(|p| p.is_due(now)) {
+            return;
+        }
+
+        // Mismo orden de locks en todo rustask (comandos, TUI, guardado y el resto
+        // del scheduler): storage, config y por último pomodoro
         let mut storage = storage.lock().unwrap();
-        let tasks = storage.get_all_tasks_mut();
-        
-        for task in tasks.iter_mut() {
-            if task.is_completed() {
-                continue;
-            }
+        let config = config.lock().unwrap();
+        let mut pomodoro = pomodoro.lock().unwrap();
+        let Some(current) = pomodoro.as_mut().filter(|p| p.is_due(now)) else { return };
 
-            if task.is_due() {
-                Self::send_notification(task);
-                task.mark_reminder_sent();
+        // La tarea se completó o se eliminó mientras tanto
+        let Some(task) = storage.find_task_by_id_mut(current.task_id).filter(|t| !t.is_completed()) else {
+            *pomodoro = None;
+            return;
+        };
+
+        let event = match current.advance(&config, now) {
+            Transition::FocusEnded => {
+                task.pomodoros.push(now.with_timezone(&chrono::Utc));
+                TaskEvent::PomodoroCompleted
             }
+            Transition::BreakEnded => TaskEvent::BreakEnded,
+        };
+
+        // Con 'dnd on' o en horas de silencio el ciclo sigue pero no se avisa.
+        // A diferencia de los recordatorios no se difiere: el aviso ya no serviría
+        if config.dnd || config.quiet_until(now).is_some() {
+            return;
         }
+        notifiers.notify(&config, event, task);
     }
 
-    fn send_notification(task: &crate::task::Task) {
-        println!("\n🔔 ═══════════════════════════════════");
-        println!("   ⏰ RECORDATORIO");
-        println!("   📝 Tarea: {}", task.title);
-        
-        if let Some(desc) = &task.description {
-            println!("   📄 {}", desc);
-        }
-        
-        if let Some(scheduled) = task.scheduled_for {
-            println!("   🕐 Programada: {}", scheduled.format("%d/%m/%Y %H:%M"));
-        }
-        
-        if !task.tags.is_empty() {
-            println!("   🏷️  Tags: {}", task.tags.join(", "));
+    // Envía la agenda diaria por email una vez al día, a la hora configurada
+    fn check_daily_agenda(storage: &Arc<Mutex<TaskStorage>>, config: &Arc<Mutex<Config>>) {
+        let now = Local::now();
+        let storage = storage.lock().unwrap();
+        let mut config = config.lock().unwrap();
+        if !email::is_agenda_due(&config, now) {
+            return;
         }
+        config.last_agenda_sent = Some(now.date_naive());
         
-        println!("   💡 Usa 'snooze {}' para posponer", task.id);
-        println!("═══════════════════════════════════\n");
+        let tasks: Vec<&Task> = storage.get_all_tasks().iter().collect();
+        let (subject, plain, html) = email::render_agenda(&tasks, now.date_naive());
         
-        // Enviar notificación del sistema en macOS
-        #[cfg(target_os = "macos")]
-        {
-            Self::send_macos_notification(task);
+        if let Some(email_config) = config.email.clone() {
+            thread::spawn(move || {
+                if let Err(e) = email::send_email(&email_config, &subject, plain, html) {
+                    eprintln!("⚠️ Error al enviar la agenda por email: {}", e);
+                }
+            });
         }
     }
-    
-    // Envía notificaciones nativas de macOS usando terminal-notifier
-    #[cfg(target_os = "macos")]
-    fn send_macos_notification(task: &crate::task::Task) {
-        use std::process::Command;
-        
-        // Construir el mensaje de la notificacion
-        let mut message = task.title.clone();
-        if let Some(desc) = &task.description {
-            message.push_str("\n");
-            message.push_str(desc);
-        }
-        
-        // Ejecutar terminal-notifier para mostrar la notificacion
-        let result = Command::new("terminal-notifier")
-            .args(&[
-                "-title", "🦀 RusTask",
-                "-subtitle", "Recordatorio de Tarea",
-                "-message", &message,
-                "-sound", "Glass",
-                "-sender", "com.apple.Terminal",
-            ])
-            .output();
+
+    fn check_due_tasks(storage: &Arc<Mutex<TaskStorage>>, config: &Arc<Mutex<Config>>, notifiers: &Notifiers) {
+        let mut storage = storage.lock().unwrap();
+        let config = config.lock().unwrap();
+        let overdue_grace = chrono::Duration::minutes(config.overdue_after_minutes);
+        let tasks = storage.get_all_tasks_mut();
         
-        // Verificar si la notificacion se envió correctamente
-        match result {
-            Ok(output) if output.status.success() => {
-                println!("✅ Notificación enviada");
+        for task in tasks.iter_mut() {
+            if task.is_completed() {
+                continue;
             }
-            Ok(output) => {
-                eprintln!("⚠️ Error al enviar notificación: {}", 
-                         String::from_utf8_lossy(&output.stderr));
+
+            let due = task.is_due();
+            let overdue = task.is_overdue(overdue_grace);
+            if !due && !overdue {
+                continue;
             }
-            Err(e) => {
-                eprintln!("⚠️ terminal-notifier no disponible: {}", e);
-                eprintln!("   Instala con: brew install terminal-notifier");
+
+            // Con 'dnd on' no se notifica: la tarea sigue vencida y se
+            // avisará en cuanto se desactive
+            if config.dnd {
+                continue;
             }
-        }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    #[cfg(target_os = "macos")]
-    fn test_macos_notification() {
-        use std::process::Command;
-        
-        println!("🧪 Probando notificación de macOS...");
-        
-        let result = Command::new("terminal-notifier")
-            .args(&[
-                "-title", "RusTask Test",
-                "-message", "Esta es una notificación de prueba",
-                "-sound", "Glass",
-                "-sender", "com.apple.Terminal",
-            ])
-            .output();
-        
-        match result {
-            Ok(output) if output.status.success() => {
-                println!("✅ Notificación enviada exitosamente");
-                println!("   Deberías ver una notificación en la esquina superior derecha");
+            // En horas de silencio el recordatorio se difiere al final de la ventana
+            if let Some(until) = config.quiet_until(Local::now()) {
+                if due {
+                    task.defer_until(until);
+                }
+                continue;
             }
-            Ok(output) => {
-                println!("❌ Error al enviar notificación");
-                println!("   stderr: {}", String::from_utf8_lossy(&output.stderr));
-                panic!("La notificación falló");
+
+            if due {
+                notifiers.notify(&config, TaskEvent::Reminder, task);
+                task.mark_reminder_sent();
+                task.clear_snooze();
             }
-            Err(e) => {
-                println!("❌ Error ejecutando terminal-notifier: {}", e);
-                println!("   Instala con: brew install terminal-notifier");
-                panic!("No se pudo ejecutar terminal-notifier");
+
+            if overdue {
+                notifiers.notify(&config, TaskEvent::Overdue, task);
+                task.mark_overdue_notified();
             }
         }
     }
-}
\ No newline at end of file
+}
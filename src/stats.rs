@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use serde::Serialize;
+use crate::config::Config;
+use crate::storage::TaskStats;
+use crate::task::Task;
+
+// Estadísticas de 'stats'. Todo se calcula a partir de las tareas y se puede
+// mostrar en la terminal o como JSON (los tiempos van en horas).
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    #[serde(flatten)]
+    pub counts: TaskStats,
+    // Pendientes cuya fecha límite u hora programada ya pasó
+    pub overdue: usize,
+    pub lead_time: Option<LeadTime>,
+    pub completed_per_day: Vec<DayCount>,
+    pub completed_per_week: Vec<WeekCount>,
+    pub tags: Vec<TagStats>,
+    pub snoozes: SnoozeStats,
+    pub trend: Trend,
+    pub pomodoros: PomodoroStats,
+}
+
+// Tiempo desde que se crea una tarea hasta que se completa
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LeadTime {
+    pub average_hours: f64,
+    pub median_hours: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayCount {
+    pub date: NaiveDate,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeekCount {
+    // Semana ISO, ej: "2025-W23"
+    pub week: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagStats {
+    pub tag: String,
+    pub total: usize,
+    pub completed: usize,
+    pub overdue: usize,
+    pub average_lead_time_hours: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SnoozeStats {
+    pub total: u32,
+    pub snoozed_tasks: usize,
+    pub average_per_snoozed_task: f64,
+    // Tareas con más snoozes que 'snooze_limit'
+    pub escalated: usize,
+}
+
+// Los últimos `window_days` días frente a los anteriores
+#[derive(Debug, Serialize)]
+pub struct Trend {
+    pub window_days: u32,
+    pub current: WindowStats,
+    pub previous: WindowStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WindowStats {
+    pub created: usize,
+    pub completed: usize,
+    pub average_lead_time_hours: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PomodoroStats {
+    pub total: usize,
+    pub per_task: Vec<TaskCount>,
+    pub per_day: Vec<DayCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskCount {
+    pub id: u64,
+    pub title: String,
+    pub count: usize,
+}
+
+// `window_days`: días por fila en completed_per_day y tamaño de la ventana de
+// tendencias; `weeks`: semanas en completed_per_week
+pub fn build(counts: TaskStats, tasks: &[Task], config: &Config, now: DateTime<Local>, window_days: u32, weeks: u32) -> Report {
+    let today = now.date_naive();
+
+    Report {
+        counts,
+        overdue: tasks.iter().filter(|t| is_overdue(t, now)).count(),
+        lead_time: lead_time(tasks.iter()),
+        completed_per_day: (0..window_days)
+            .rev()
+            .map(|offset| today - Duration::days(offset as i64))
+            .map(|date| DayCount { date, count: completed_on(tasks, |day| day == date) })
+            .collect(),
+        completed_per_week: (0..weeks)
+            .rev()
+            .map(|offset| (today - Duration::weeks(offset as i64)).iso_week())
+            .map(|week| WeekCount {
+                week: format!("{}-W{:02}", week.year(), week.week()),
+                count: completed_on(tasks, |day| day.iso_week() == week),
+            })
+            .collect(),
+        tags: tag_stats(tasks, now),
+        snoozes: snooze_stats(tasks, config),
+        trend: trend(tasks, today, window_days),
+        pomodoros: pomodoro_stats(tasks),
+    }
+}
+
+fn is_overdue(task: &Task, now: DateTime<Local>) -> bool {
+    !task.is_completed() && (task.due.is_some_and(|d| d < now) || task.scheduled_for.is_some_and(|s| s < now))
+}
+
+fn completed_day(task: &Task) -> Option<NaiveDate> {
+    task.completed_at.map(|c| c.with_timezone(&Local).date_naive())
+}
+
+fn completed_on(tasks: &[Task], matches: impl Fn(NaiveDate) -> bool) -> usize {
+    tasks.iter().filter_map(completed_day).filter(|day| matches(*day)).count()
+}
+
+fn lead_time<'a>(tasks: impl Iterator<Item = &'a Task>) -> Option<LeadTime> {
+    let mut hours: Vec<f64> = tasks
+        .filter_map(|t| t.completed_at.map(|c| (c - t.created_at).num_minutes() as f64 / 60.0))
+        .collect();
+    if hours.is_empty() {
+        return None;
+    }
+
+    hours.sort_by(|a, b| a.total_cmp(b));
+    let middle = hours.len() / 2;
+    let median_hours = if hours.len().is_multiple_of(2) { (hours[middle - 1] + hours[middle]) / 2.0 } else { hours[middle] };
+
+    Some(LeadTime { average_hours: hours.iter().sum::<f64>() / hours.len() as f64, median_hours })
+}
+
+// Por tag, de más a menos tareas
+pub fn tag_stats(tasks: &[Task], now: DateTime<Local>) -> Vec<TagStats> {
+    let mut by_tag: BTreeMap<&str, Vec<&Task>> = BTreeMap::new();
+    for task in tasks {
+        for tag in &task.tags {
+            by_tag.entry(tag).or_default().push(task);
+        }
+    }
+
+    let mut stats: Vec<TagStats> = by_tag.into_iter()
+        .map(|(tag, tasks)| TagStats {
+            tag: tag.to_string(),
+            total: tasks.len(),
+            completed: tasks.iter().filter(|t| t.is_completed()).count(),
+            overdue: tasks.iter().filter(|t| is_overdue(t, now)).count(),
+            average_lead_time_hours: lead_time(tasks.into_iter()).map(|l| l.average_hours),
+        })
+        .collect();
+    stats.sort_by_key(|s| std::cmp::Reverse(s.total));
+    stats
+}
+
+fn snooze_stats(tasks: &[Task], config: &Config) -> SnoozeStats {
+    let snoozed: Vec<u32> = tasks.iter().map(|t| t.snooze_count).filter(|c| *c > 0).collect();
+    let total: u32 = snoozed.iter().sum();
+
+    SnoozeStats {
+        total,
+        snoozed_tasks: snoozed.len(),
+        average_per_snoozed_task: if snoozed.is_empty() { 0.0 } else { total as f64 / snoozed.len() as f64 },
+        escalated: snoozed.iter().filter(|c| config.is_snooze_escalated(**c)).count(),
+    }
+}
+
+fn trend(tasks: &[Task], today: NaiveDate, window_days: u32) -> Trend {
+    let window = Duration::days(window_days as i64);
+    // La ventana actual termina hoy, inclusive
+    let current_start = today - window + Duration::days(1);
+    let previous_start = current_start - window;
+
+    let window_stats = |start: NaiveDate, end: NaiveDate| {
+        let in_window = |day: NaiveDate| day >= start && day < end;
+        WindowStats {
+            created: tasks.iter().filter(|t| in_window(t.created_at.with_timezone(&Local).date_naive())).count(),
+            completed: completed_on(tasks, in_window),
+            average_lead_time_hours: lead_time(tasks.iter().filter(|t| completed_day(t).is_some_and(in_window)))
+                .map(|l| l.average_hours),
+        }
+    };
+
+    Trend {
+        window_days,
+        current: window_stats(current_start, today + Duration::days(1)),
+        previous: window_stats(previous_start, current_start),
+    }
+}
+
+fn pomodoro_stats(tasks: &[Task]) -> PomodoroStats {
+    let mut per_task: Vec<TaskCount> = tasks.iter()
+        .filter(|t| !t.pomodoros.is_empty())
+        .map(|t| TaskCount { id: t.id, title: t.title.clone(), count: t.pomodoros.len() })
+        .collect();
+    per_task.sort_by_key(|t| std::cmp::Reverse(t.count));
+
+    let mut per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for pomodoro in tasks.iter().flat_map(|t| &t.pomodoros) {
+        *per_day.entry(pomodoro.with_timezone(&Local).date_naive()).or_insert(0) += 1;
+    }
+
+    PomodoroStats {
+        total: per_task.iter().map(|t| t.count).sum(),
+        per_task,
+        per_day: per_day.into_iter().map(|(date, count)| DayCount { date, count }).collect(),
+    }
+}
+
+// "45m", "5h 30m", "2d 3h"
+pub fn format_hours(hours: f64) -> String {
+    let minutes = (hours * 60.0).round() as i64;
+    match minutes {
+        m if m < 60 => format!("{}m", m),
+        m if m < 24 * 60 => format!("{}h {:02}m", m / 60, m % 60),
+        m => format!("{}d {}h", m / (24 * 60), (m % (24 * 60)) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn counts(tasks: &[Task]) -> TaskStats {
+        let completed = tasks.iter().filter(|t| t.is_completed()).count();
+        TaskStats { total: tasks.len(), completed, pending: tasks.len() - completed }
+    }
+
+    fn now() -> DateTime<Local> {
+        Local.from_local_datetime(&NaiveDate::from_ymd_opt(2025, 6, 11).unwrap().and_hms_opt(12, 0, 0).unwrap()).unwrap()
+    }
+
+    // Creada hace `created_days_ago` días y completada `lead_hours` horas después
+    fn task(id: u64, tags: &[&str], created_days_ago: i64, lead_hours: Option<i64>) -> Task {
+        let mut task = Task::new_with_tags(id, format!("Tarea {}", id), None, tags.iter().map(|t| t.to_string()).collect());
+        task.created_at = (now() - Duration::days(created_days_ago)).with_timezone(&Utc);
+        if let Some(hours) = lead_hours {
+            task.complete();
+            task.completed_at = Some(task.created_at + Duration::hours(hours));
+        }
+        task
+    }
+
+    #[test]
+    fn test_lead_time_tags_and_overdue() {
+        let mut tasks = vec![
+            task(1, &["web"], 3, Some(2)),
+            task(2, &["web", "api"], 2, Some(10)),
+            task(3, &["api"], 1, Some(30)),
+            task(4, &["web"], 1, None),
+        ];
+        tasks[3].due = Some(now() - Duration::hours(1));
+        tasks[3].snooze_count = 5;
+
+        let report = build(counts(&tasks), &tasks, &Config::default(), now(), 7, 2);
+        assert_eq!(report.counts.completed, 3);
+        assert_eq!(report.overdue, 1);
+        assert_eq!(report.lead_time, Some(LeadTime { average_hours: 14.0, median_hours: 10.0 }));
+
+        let web = &report.tags[0];
+        assert_eq!((web.tag.as_str(), web.total, web.completed, web.overdue), ("web", 3, 2, 1));
+        assert_eq!(web.average_lead_time_hours, Some(6.0));
+
+        assert_eq!(report.snoozes.total, 5);
+        assert_eq!(report.snoozes.escalated, 1);
+    }
+
+    #[test]
+    fn test_completions_per_day_and_trend() {
+        let tasks = vec![
+            task(1, &[], 0, Some(0)),
+            task(2, &[], 2, Some(1)),
+            task(3, &[], 3, Some(1)),
+            // En la ventana anterior
+            task(4, &[], 9, Some(24)),
+        ];
+
+        let report = build(counts(&tasks), &tasks, &Config::default(), now(), 7, 2);
+        let per_day: Vec<usize> = report.completed_per_day.iter().map(|d| d.count).collect();
+        assert_eq!(per_day, vec![0, 0, 0, 1, 1, 0, 1]);
+        assert_eq!(report.completed_per_day.last().unwrap().date, now().date_naive());
+
+        // La semana 24 empieza el lunes 09/06: las tareas 3 (08/06) y 4 (02/06) son de la 23
+        assert_eq!(report.completed_per_week.iter().map(|w| (w.week.as_str(), w.count)).collect::<Vec<_>>(),
+                   vec![("2025-W23", 2), ("2025-W24", 2)]);
+
+        assert_eq!(report.trend.current.completed, 3);
+        assert_eq!(report.trend.current.created, 3);
+        assert_eq!(report.trend.previous.completed, 1);
+        assert_eq!(report.trend.previous.average_lead_time_hours, Some(24.0));
+
+        assert_eq!(format_hours(0.5), "30m");
+        assert_eq!(format_hours(26.0), "1d 2h");
+    }
+}
@@ -1,20 +1,57 @@
 use std::fs;
 use std::io;
 use std::path::Path;
+use std::collections::HashSet;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use crate::eventlog::{self, Replay};
+use crate::formats::todotxt;
+use crate::migrations;
 use crate::task::Task;
 
+// Formato del archivo principal de tareas
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageFormat {
+    #[default]
+    Json,
+    // Un archivo todo.txt que también pueden editar otras herramientas
+    #[value(name = "todotxt")]
+    TodoTxt,
+    // Registro de eventos con snapshots (ver eventlog.rs)
+    #[value(name = "eventlog")]
+    EventLog,
+}
+
 // Struct helper para estadísticas
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct TaskStats {
     pub total: usize,
     pub completed: usize,
     pub pending: usize,
 }
 
+// Largo mínimo de un prefijo de UUID, para no confundirlo con un ID corto
+const MIN_UUID_PREFIX: usize = 4;
+
+// Contenido de tasks.json (ver migrations.rs)
+#[derive(Deserialize)]
+struct DataFile {
+    next_id: u64,
+    tasks: Vec<Task>,
+}
+
 #[derive(Debug)]
 pub struct TaskStorage {
     tasks: Vec<Task>,
     next_id: u64,
+    // Registro de eventos cargado y su estado al guardarlo por última vez,
+    // para escribir solo lo que cambió
+    event_log: Option<(String, Replay)>,
+    // Líneas de todo.txt que no son tareas válidas (ej: sin título). Se
+    // escriben tal cual al guardar para no romper el archivo del usuario.
+    todo_txt_unparsed: Vec<String>,
 }
 
 impl TaskStorage {
@@ -22,6 +59,8 @@ impl TaskStorage {
         Self {
             tasks: Vec::new(),
             next_id: 1,
+            event_log: None,
+            todo_txt_unparsed: Vec::new(),
         }
     }
 
@@ -44,6 +83,37 @@ impl TaskStorage {
         self.tasks.iter().find(|task| task.id == id)
     }
 
+    // Resolver un ID corto ("12") o un prefijo de UUID ("4f6c2a") al ID corto.
+    // Un número siempre es un ID: si no existe es un error, nunca un prefijo
+    // (ej: 'delete 1234' con la tarea 1234 ya eliminada no debe borrar otra)
+    pub fn resolve_id(&self, reference: &str) -> Result<u64, String> {
+        if let Ok(id) = reference.parse::<u64>() {
+            return match self.find_task_by_id(id) {
+                Some(_) => Ok(id),
+                None => Err(format!("No se encontró una tarea con ID {}", reference)),
+            };
+        }
+        
+        let prefix = reference.to_lowercase();
+        let is_uuid_prefix = prefix.len() >= MIN_UUID_PREFIX
+            && prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+        if !is_uuid_prefix {
+            return Err(format!("No se encontró una tarea con ID {}", reference));
+        }
+        
+        let matches: Vec<u64> = self.tasks
+            .iter()
+            .filter(|task| task.uuid.to_string().starts_with(&prefix))
+            .map(|task| task.id)
+            .collect();
+        
+        match matches.as_slice() {
+            [id] => Ok(*id),
+            [] => Err(format!("No se encontró una tarea con ID o UUID {}", reference)),
+            _ => Err(format!("El prefijo '{}' coincide con {} tareas, usa más caracteres", reference, matches.len())),
+        }
+    }
+
     // Encontrar una tarea mutable por ID (para modificarla)
     pub fn find_task_by_id_mut(&mut self, id: u64) -> Option<&mut Task> {
         self.tasks.iter_mut().find(|task| task.id == id)
@@ -66,16 +136,6 @@ impl TaskStorage {
         }
     }
 
-    // Completar una tarea (método de conveniencia)
-    pub fn complete_task(&mut self, id: u64) -> bool {
-        if let Some(task) = self.find_task_by_id_mut(id) {
-            task.complete();  // Usa el método complete() de Task
-            true
-        } else {
-            false
-        }
-    }
-
     // Obtener tareas por estado
     pub fn get_tasks_by_status(&self, completed: bool) -> impl Iterator<Item=&Task> {
         self.tasks
@@ -105,9 +165,14 @@ impl TaskStorage {
     }
 
     pub fn save_to_file(&self, path: &str) -> Result<(), io::Error> {
-      // 1. Serializar las tareas a JSON (formato pretty para que sea legible)
-      let json: String = serde_json::to_string_pretty(&self.tasks)
-          .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+      // 1. Serializar las tareas dentro del sobre versionado (pretty para que sea legible)
+      let data = json!({
+          "version": migrations::CURRENT_VERSION,
+          "next_id": self.next_id,
+          "tasks": &self.tasks,
+      });
+      let json: String = serde_json::to_string_pretty(&data)
+          .map_err(io::Error::other)?;
       
       // 2. Escribir el JSON al archivo
       fs::write(path, json)?;
@@ -122,19 +187,136 @@ impl TaskStorage {
       
       // 1. Leer el contenido del archivo
       let data = fs::read_to_string(path)?;
+      let data: serde_json::Value = serde_json::from_str(&data)
+          .map_err(io::Error::other)?;
       
-      // 2. Deserializar el JSON a un vector de tareas
-      let tasks: Vec<Task> = serde_json::from_str(&data)
-          .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+      // 2. Actualizar archivos de versiones anteriores, guardando antes una copia
+      let version = migrations::file_version(&data).map_err(io::Error::other)?;
+      let data = migrations::migrate(data).map_err(io::Error::other)?;
+      if version < migrations::CURRENT_VERSION {
+          let backup = format!("{}.v{}.bak", path, version);
+          fs::copy(path, &backup)?;
+          println!("🔄 {} actualizado de la versión {} a la {} (copia en {})",
+                   path, version, migrations::CURRENT_VERSION, backup);
+      }
       
-      // 3. Actualizar el almacenamiento con las tareas cargadas
-      // El next_id debe ser mayor que el ID más alto para evitar duplicados
-      self.next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
-      self.tasks = tasks;
+      // 3. Deserializar el sobre
+      let file: DataFile = serde_json::from_value(data)
+          .map_err(io::Error::other)?;
       
+      // 4. Actualizar el almacenamiento con las tareas cargadas
+      // El next_id nunca puede ser menor que el ID más alto + 1
+      let max_id = file.tasks.iter().map(|t| t.id).max().unwrap_or(0);
+      self.next_id = file.next_id.max(max_id + 1);
+      self.tasks = file.tasks;
+      
+      Ok(())
+    }
+
+    pub fn save_to_todo_txt(&self, path: &str) -> Result<(), io::Error> {
+      let mut buffer = format!("{}\n", todotxt::format_next_id_header(self.next_id)).into_bytes();
+      todotxt::export(&self.tasks, &mut buffer).map_err(io::Error::other)?;
+      for line in &self.todo_txt_unparsed {
+          buffer.extend_from_slice(line.as_bytes());
+          buffer.push(b'\n');
+      }
+      fs::write(path, buffer)
+    }
+
+    pub fn load_from_todo_txt(&mut self, path: &str) -> Result<(), io::Error> {
+      if !Path::new(path).exists() {
+          return Ok(());
+      }
+
+      let data = fs::read_to_string(path)?;
+
+      // Una línea que no es una tarea se avisa y se conserva tal cual, sin
+      // volver a escribirla como tarea (cambiaría en cada guardado)
+      let mut tasks = Vec::new();
+      let mut next_id = 1;
+      self.todo_txt_unparsed.clear();
+      for (i, line) in data.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+          if let Some(header) = todotxt::parse_next_id_header(line) {
+              next_id = header;
+              continue;
+          }
+          match todotxt::parse_line(line) {
+              Ok(task) => tasks.push(task),
+              Err(e) => {
+                  eprintln!("⚠️ {} línea {}: {} (se conserva sin cambios)", path, i + 1, e);
+                  self.todo_txt_unparsed.push(line.to_string());
+              },
+          }
+      }
+
+      // Las líneas agregadas por otras herramientas no tienen id: (o lo repiten).
+      // Igual que en JSON, el next_id nunca es menor que el ID más alto + 1.
+      self.next_id = next_id.max(tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1);
+      let mut seen = HashSet::new();
+      self.tasks = Vec::with_capacity(tasks.len());
+      for mut task in tasks {
+          if task.id == 0 || !seen.insert(task.id) {
+              task.id = self.next_id;
+              self.next_id += 1;
+          }
+          self.tasks.push(task);
+      }
+
+      Ok(())
+    }
+
+    // Agrega al registro los eventos que llevan del último estado guardado al actual
+    pub fn save_to_event_log(&mut self, path: &str) -> Result<(), io::Error> {
+      let replay = match self.event_log.take() {
+          Some((loaded, replay)) if loaded == path => replay,
+          // Otro archivo (ej: al cambiar de formato o restaurar una copia):
+          // los eventos se calculan contra lo que ya tiene
+          _ => eventlog::replay(path)?,
+      };
+      let (_, replay) = self.event_log.insert((path.to_string(), replay));
+
+      let events = eventlog::diff(&replay.tasks, &self.tasks);
+      eventlog::append(path, replay, events, &self.tasks, self.next_id)
+    }
+
+    pub fn load_from_event_log(&mut self, path: &str) -> Result<(), io::Error> {
+      let replay = eventlog::replay(path)?;
+
+      let max_id = replay.tasks.iter().map(|t| t.id).max().unwrap_or(0);
+      self.next_id = replay.next_id.max(max_id + 1);
+      self.tasks = replay.tasks.clone();
+      self.event_log = Some((path.to_string(), replay));
+
       Ok(())
     }
 
+    // Reescribe el registro como una sola snapshot. Retorna cuántos eventos había.
+    pub fn compact_event_log(&mut self, path: &str) -> Result<usize, io::Error> {
+      self.save_to_event_log(path)?;
+      let events = eventlog::read_events(path)?.len();
+
+      if let Some((_, replay)) = self.event_log.as_mut() {
+          eventlog::compact(path, replay, &self.tasks, self.next_id)?;
+      }
+      Ok(events)
+    }
+
+    pub fn save(&mut self, format: StorageFormat, path: &str) -> Result<(), io::Error> {
+        match format {
+            StorageFormat::Json => self.save_to_file(path),
+            StorageFormat::TodoTxt => self.save_to_todo_txt(path),
+            StorageFormat::EventLog => self.save_to_event_log(path),
+        }
+    }
+
+    pub fn load(&mut self, format: StorageFormat, path: &str) -> Result<(), io::Error> {
+        match format {
+            StorageFormat::Json => self.load_from_file(path),
+            StorageFormat::TodoTxt => self.load_from_todo_txt(path),
+            StorageFormat::EventLog => self.load_from_event_log(path),
+        }
+    }
+
     // Obtener tareas mutables (necesario para el scheduler)
     pub fn get_all_tasks_mut(&mut self) -> &mut Vec<Task> {
         &mut self.tasks
@@ -146,14 +328,120 @@ impl TaskStorage {
             .iter()
             .filter(|t| !t.is_completed() && t.scheduled_for.is_some())
     }
+}
 
-    // Aplicar snooze a una tarea
-    pub fn snooze_task(&mut self, id: u64, minutes: i64) -> bool {
-        if let Some(task) = self.find_task_by_id_mut(id) {
-            task.snooze(minutes);
-            true
-        } else {
-            false
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_legacy_file_is_migrated_and_ids_are_not_reused() {
+        let dir = std::env::temp_dir().join(format!("rustask-storage-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tasks.json");
+        let path = path.to_str().unwrap();
+
+        // Archivo de la versión 0: un array de tareas
+        let legacy = vec![Task::new(1, "Uno".to_string(), None), Task::new(2, "Dos".to_string(), None)];
+        fs::write(path, serde_json::to_string(&legacy).unwrap()).unwrap();
+
+        let mut storage = TaskStorage::new();
+        storage.load_from_file(path).unwrap();
+        assert_eq!(storage.get_all_tasks().len(), 2);
+        assert!(Path::new(&format!("{}.v0.bak", path)).exists());
+
+        // Borrar la tarea con el ID más alto no debe hacer que se reutilice
+        storage.delete_task(2);
+        storage.save_to_file(path).unwrap();
+
+        let mut reloaded = TaskStorage::new();
+        reloaded.load_from_file(path).unwrap();
+        assert_eq!(reloaded.add_task(Task::new(0, "Tres".to_string(), None)), 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_todo_txt_lines_do_not_change_between_loads() {
+        let dir = std::env::temp_dir().join(format!("rustask-storage-todotxt-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("todo.txt");
+        let path = path.to_str().unwrap();
+        fs::write(path, "(A) Buy milk @home due:tomorrow\n(A) +solo @tags\n").unwrap();
+
+        let mut storage = TaskStorage::new();
+        storage.load_from_todo_txt(path).unwrap();
+        storage.save_to_todo_txt(path).unwrap();
+        let saved = fs::read_to_string(path).unwrap();
+        assert!(saved.ends_with("(A) +solo @tags\n"));
+
+        // Cargar y guardar otra vez no agrega fechas, IDs ni UUIDs nuevos
+        let mut reloaded = TaskStorage::new();
+        reloaded.load_from_todo_txt(path).unwrap();
+        reloaded.save_to_todo_txt(path).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), saved);
+        assert_eq!(reloaded.get_all_tasks()[0].title, "Buy milk due:tomorrow");
+
+        // Borrar la tarea con el ID más alto no debe hacer que se reutilice
+        reloaded.add_task(Task::new(0, "Dos".to_string(), None));
+        reloaded.delete_task(2);
+        reloaded.save_to_todo_txt(path).unwrap();
+        let mut after_delete = TaskStorage::new();
+        after_delete.load_from_todo_txt(path).unwrap();
+        assert_eq!(after_delete.add_task(Task::new(0, "Tres".to_string(), None)), 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_todo_txt_keeps_descriptions_and_extras() {
+        let dir = std::env::temp_dir().join(format!("rustask-storage-todotxt-desc-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("todo.txt");
+        let path = path.to_str().unwrap();
+
+        let mut storage = TaskStorage::new();
+        let mut task = Task::new(0, "Viaje".to_string(), Some("Reservar hotel y tren".to_string()));
+        task.extras.insert("recur".to_string(), serde_json::json!("weekly"));
+        storage.add_task(task);
+        storage.save_to_todo_txt(path).unwrap();
+
+        let mut reloaded = TaskStorage::new();
+        reloaded.load_from_todo_txt(path).unwrap();
+        let task = &reloaded.get_all_tasks()[0];
+        assert_eq!(task.description.as_deref(), Some("Reservar hotel y tren"));
+        assert_eq!(task.extras["recur"], "weekly");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_id_by_number_or_uuid_prefix() {
+        let mut storage = TaskStorage::new();
+        let mut first = Task::new(0, "Uno".to_string(), None);
+        first.uuid = Uuid::parse_str("4f6c2a1e-8d7b-4c1e-9f3a-2b5d6e7f8a9b").unwrap();
+        let mut second = Task::new(0, "Dos".to_string(), None);
+        second.uuid = Uuid::parse_str("4f6c9999-0000-4000-8000-000000000000").unwrap();
+        storage.add_task(first);
+        storage.add_task(second);
+
+        assert_eq!(storage.resolve_id("2"), Ok(2));
+        assert_eq!(storage.resolve_id("4F6C2A"), Ok(1));
+        assert!(storage.resolve_id("4f6c").unwrap_err().contains("coincide con 2 tareas"));
+        // Prefijos demasiado cortos no se buscan
+        assert!(storage.resolve_id("4f6").unwrap_err().contains("No se encontró"));
+        assert!(storage.resolve_id("9").is_err());
+
+        // Un número que no es un ID no se toma como prefijo de UUID
+        let mut third = Task::new(0, "Tres".to_string(), None);
+        third.uuid = Uuid::parse_str("12345678-0000-4000-8000-000000000000").unwrap();
+        storage.add_task(third);
+        assert!(storage.resolve_id("1234").unwrap_err().contains("No se encontró una tarea con ID 1234"));
+        assert_eq!(storage.resolve_id("1234567"), Err("No se encontró una tarea con ID 1234567".to_string()));
+        assert_eq!(storage.resolve_id("12345678-"), Ok(3));
     }
 }
@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Local, Utc};
-use chrono::Duration;
+use chrono::{DateTime, Duration, Local, Utc};
+use clap::ValueEnum;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TaskStatus {
@@ -8,9 +10,59 @@ pub enum TaskStatus {
     Completed,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Priority {
+    #[value(alias = "h")]
+    High,
+    #[value(alias = "m")]
+    Medium,
+    #[value(alias = "l")]
+    Low,
+}
+
+impl Priority {
+    // Letra equivalente en todo.txt: (A), (B), (C)
+    pub fn letter(&self) -> char {
+        match self {
+            Priority::High => 'A',
+            Priority::Medium => 'B',
+            Priority::Low => 'C',
+        }
+    }
+
+    // De D en adelante se considera prioridad baja
+    pub fn from_letter(letter: char) -> Option<Priority> {
+        match letter.to_ascii_uppercase() {
+            'A' => Some(Priority::High),
+            'B' => Some(Priority::Medium),
+            'C'..='Z' => Some(Priority::Low),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Priority::High => "alta",
+            Priority::Medium => "media",
+            Priority::Low => "baja",
+        }
+    }
+}
+
+// Intervalo de trabajo registrado con start/stop (sin `end` mientras sigue en marcha)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
+    // ID corto para usar en la terminal
     pub id: u64,
+    // Identificador permanente (el ID corto es solo de trabajo)
+    #[serde(default = "Uuid::new_v4")]
+    pub uuid: Uuid,
     pub title: String,
     pub description: Option<String>,
     pub tags: Vec<String>,
@@ -21,12 +73,37 @@ pub struct Task {
     pub reminder_sent: bool,
     pub snoozed_until: Option<DateTime<Local>>,
     pub snooze_count: u32,
+    #[serde(default)]
+    pub overdue_notified: bool,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub project: Option<String>,
+    // Fecha límite (a diferencia de scheduled_for, no genera recordatorios)
+    #[serde(default)]
+    pub due: Option<DateTime<Local>>,
+    // Atributos de otras herramientas que rustask no usa (ej: UDAs de Taskwarrior),
+    // guardados para no perderlos al volver a exportar
+    #[serde(default)]
+    pub extras: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    // Cuándo terminó cada pomodoro hecho sobre la tarea
+    #[serde(default)]
+    pub pomodoros: Vec<DateTime<Utc>>,
+    // Tiempo estimado para terminarla
+    #[serde(default)]
+    pub estimate_minutes: Option<i64>,
+    // Última vez que se vio en 'review'
+    #[serde(default)]
+    pub last_reviewed: Option<DateTime<Utc>>,
 }
 
 impl Task {
     pub fn new(id: u64, title: String, description: Option<String>) -> Self {
         Self {
             id,
+            uuid: Uuid::new_v4(),
             title,
             description,
             tags: Vec::new(),
@@ -37,12 +114,22 @@ impl Task {
             reminder_sent: false,
             snoozed_until: None,
             snooze_count: 0,
+            overdue_notified: false,
+            priority: None,
+            project: None,
+            due: None,
+            extras: BTreeMap::new(),
+            time_entries: Vec::new(),
+            pomodoros: Vec::new(),
+            estimate_minutes: None,
+            last_reviewed: None,
         }
     }
     
     pub fn new_with_tags(id: u64, title: String, description: Option<String>, tags: Vec<String>) -> Self {
         Self {
             id,
+            uuid: Uuid::new_v4(),
             title,
             description,
             tags,
@@ -53,12 +140,23 @@ impl Task {
             reminder_sent: false,
             snoozed_until: None,
             snooze_count: 0,
+            overdue_notified: false,
+            priority: None,
+            project: None,
+            due: None,
+            extras: BTreeMap::new(),
+            time_entries: Vec::new(),
+            pomodoros: Vec::new(),
+            estimate_minutes: None,
+            last_reviewed: None,
         }
     }
 
     pub fn complete(&mut self) {
         self.status = TaskStatus::Completed;
         self.completed_at = Some(Utc::now());
+        // Una tarea completada ya no suma tiempo
+        self.stop_timer(Utc::now());
     }
 
     pub fn is_completed(&self) -> bool {
@@ -101,13 +199,19 @@ impl Task {
     pub fn schedule_for(&mut self, datetime: DateTime<Local>) {
         self.scheduled_for = Some(datetime);
         self.reminder_sent = false;
+        self.overdue_notified = false;
     }
 
-    pub fn snooze(&mut self, minutes: i64) {
-        self.snoozed_until = Some(Local::now() + Duration::minutes(minutes));
+    pub fn snooze(&mut self, until: DateTime<Local>) {
+        self.snoozed_until = Some(until);
         self.snooze_count += 1;
     }
 
+    // Posponer el recordatorio sin contarlo como snooze (ej: horas de silencio)
+    pub fn defer_until(&mut self, datetime: DateTime<Local>) {
+        self.snoozed_until = Some(datetime);
+    }
+
     pub fn is_due(&self) -> bool {
         if let Some(snoozed) = self.snoozed_until {
             return Local::now() >= snoozed;
@@ -120,6 +224,23 @@ impl Task {
         false
     }
 
+    // Sigue pendiente `grace` después de la hora programada y aún no se avisó.
+    // Si está pospuesta, el margen cuenta desde el final del snooze.
+    pub fn is_overdue(&self, grace: Duration) -> bool {
+        if self.is_completed() || self.overdue_notified {
+            return false;
+        }
+        
+        match self.scheduled_for {
+            Some(scheduled) => Local::now() >= self.snoozed_until.unwrap_or(scheduled) + grace,
+            None => false,
+        }
+    }
+
+    pub fn mark_overdue_notified(&mut self) {
+        self.overdue_notified = true;
+    }
+
     pub fn mark_reminder_sent(&mut self) {
         self.reminder_sent = true;
     }
@@ -127,4 +248,54 @@ impl Task {
     pub fn clear_snooze(&mut self) {
         self.snoozed_until = None;
     }
-}
\ No newline at end of file
+
+    // Empieza un intervalo de trabajo; false si ya había uno en marcha
+    pub fn start_timer(&mut self, now: DateTime<Utc>) -> bool {
+        if self.is_tracking() {
+            return false;
+        }
+        self.time_entries.push(TimeEntry { start: now, end: None });
+        true
+    }
+
+    // Cierra el intervalo en marcha y retorna su duración
+    pub fn stop_timer(&mut self, now: DateTime<Utc>) -> Option<Duration> {
+        let entry = self.time_entries.last_mut().filter(|e| e.end.is_none())?;
+        entry.end = Some(now);
+        Some(now - entry.start)
+    }
+
+    pub fn is_tracking(&self) -> bool {
+        self.time_entries.last().is_some_and(|e| e.end.is_none())
+    }
+
+    pub fn estimate(&self) -> Option<Duration> {
+        self.estimate_minutes.map(Duration::minutes)
+    }
+
+    // Tiempo total registrado, contando el intervalo en marcha hasta `now`
+    pub fn tracked_time(&self, now: DateTime<Utc>) -> Duration {
+        self.time_entries.iter()
+            .map(|e| e.end.unwrap_or(now) - e.start)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snoozed_task_is_not_overdue_until_grace_after_snooze() {
+        let grace = Duration::minutes(30);
+        let mut task = Task::new(1, "Llamar".to_string(), None);
+        task.scheduled_for = Some(Local::now() - Duration::hours(2));
+        assert!(task.is_overdue(grace));
+
+        task.snooze(Local::now() + Duration::hours(1));
+        assert!(!task.is_overdue(grace));
+
+        task.snooze(Local::now() - Duration::minutes(45));
+        assert!(task.is_overdue(grace));
+    }
+}
@@ -0,0 +1,50 @@
+// Plantillas mínimas con marcadores {{nombre}}.
+// `lookup` decide el valor de cada marcador; los desconocidos se dejan tal cual.
+pub fn render<F>(template: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        match after.find("}}") {
+            Some(end) => {
+                let key = after[..end].trim();
+                match lookup(key) {
+                    Some(value) => output.push_str(&value),
+                    None => output.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                // Marcador sin cerrar: se copia el resto sin cambios
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_placeholders() {
+        let lookup = |key: &str| match key {
+            "title" => Some("Comprar pan".to_string()),
+            "id" => Some("3".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(render("[{{ id }}] {{title}}", lookup), "[3] Comprar pan");
+        assert_eq!(render("{{otro}} y {{title", lookup), "{{otro}} y {{title");
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use clap::ValueEnum;
+use crate::task::Task;
+
+// Hoja de horas a partir de los intervalos registrados con start/stop
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum TimesheetGroup {
+    #[default]
+    Day,
+    // Una tarea con varios tags suma en cada uno
+    Tag,
+    Task,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Group {
+    Day(NaiveDate),
+    Tag(String),
+    // Después de los tags, por el orden de las variantes
+    Untagged,
+    Task,
+}
+
+impl Group {
+    pub fn label(&self) -> String {
+        match self {
+            Group::Day(day) => day.format("%d/%m/%Y").to_string(),
+            Group::Tag(tag) => tag.clone(),
+            Group::Untagged => "Sin tag".to_string(),
+            Group::Task => String::new(),
+        }
+    }
+}
+
+// Tiempo de una tarea dentro de un grupo
+#[derive(Debug)]
+pub struct Row {
+    pub group: Group,
+    pub task_id: u64,
+    pub title: String,
+    pub duration: Duration,
+}
+
+#[derive(Debug)]
+pub struct Timesheet {
+    pub rows: Vec<Row>,
+    // Tiempo real trabajado (sin contar dos veces las tareas con varios tags)
+    pub total: Duration,
+}
+
+// Intervalos entre `from` y `to` (días locales, ambos incluidos), los que siguen
+// en marcha cuentan hasta `now`
+pub fn build(tasks: &[Task], from: Option<NaiveDate>, to: Option<NaiveDate>, group_by: TimesheetGroup, now: DateTime<Utc>) -> Timesheet {
+    let mut totals: BTreeMap<(Group, u64), Duration> = BTreeMap::new();
+    let mut total = Duration::zero();
+
+    for task in tasks {
+        let pieces = task.time_entries.iter()
+            .flat_map(|e| split_by_day(e.start.with_timezone(&Local), e.end.unwrap_or(now).with_timezone(&Local)))
+            .filter(|(day, _)| from.is_none_or(|from| *day >= from) && to.is_none_or(|to| *day <= to));
+
+        for (day, duration) in pieces {
+            total += duration;
+
+            let groups = match group_by {
+                TimesheetGroup::Day => vec![Group::Day(day)],
+                TimesheetGroup::Tag if task.tags.is_empty() => vec![Group::Untagged],
+                TimesheetGroup::Tag => task.tags.iter().map(|t| Group::Tag(t.clone())).collect(),
+                TimesheetGroup::Task => vec![Group::Task],
+            };
+            for group in groups {
+                *totals.entry((group, task.id)).or_insert_with(Duration::zero) += duration;
+            }
+        }
+    }
+
+    let rows = totals.into_iter()
+        .map(|((group, task_id), duration)| Row {
+            group,
+            task_id,
+            title: tasks.iter().find(|t| t.id == task_id).map(|t| t.title.clone()).unwrap_or_default(),
+            duration,
+        })
+        .collect();
+
+    Timesheet { rows, total }
+}
+
+// Parte un intervalo en trozos de un día (hora local)
+fn split_by_day(start: DateTime<Local>, end: DateTime<Local>) -> Vec<(NaiveDate, Duration)> {
+    let mut pieces = Vec::new();
+    let mut current = start;
+
+    while current < end {
+        let day = current.date_naive();
+        let midnight = day.succ_opt()
+            .and_then(|next| Local.from_local_datetime(&next.and_hms_opt(0, 0, 0)?).earliest());
+        let piece_end = midnight.map_or(end, |midnight| midnight.min(end));
+
+        pieces.push((day, piece_end - current));
+        current = piece_end;
+    }
+
+    pieces
+}
+
+// Columnas: group,id,title,minutes,hours (los días en formato ISO)
+pub fn write_csv<W: Write>(timesheet: &Timesheet, writer: W) -> Result<(), String> {
+    let mut csv_writer = ::csv::Writer::from_writer(writer);
+    csv_writer.write_record(["group", "id", "title", "minutes", "hours"]).map_err(|e| e.to_string())?;
+
+    for row in &timesheet.rows {
+        let group = match &row.group {
+            Group::Day(day) => day.format("%Y-%m-%d").to_string(),
+            Group::Tag(tag) => tag.clone(),
+            Group::Untagged | Group::Task => String::new(),
+        };
+        let minutes = row.duration.num_minutes();
+        csv_writer.write_record([
+            group,
+            row.task_id.to_string(),
+            row.title.clone(),
+            minutes.to_string(),
+            format!("{:.2}", minutes as f64 / 60.0),
+        ]).map_err(|e| e.to_string())?;
+    }
+
+    csv_writer.flush().map_err(|e| e.to_string())
+}
+
+// "1h 05m", "40m"
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TimeEntry;
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        let dt = NaiveDate::from_ymd_opt(2025, 6, day).unwrap().and_hms_opt(hour, minute, 0).unwrap();
+        Local.from_local_datetime(&dt).unwrap().with_timezone(&Utc)
+    }
+
+    fn tasks() -> Vec<Task> {
+        let mut call = Task::new_with_tags(1, "Llamar a Ana".to_string(), None, vec!["cliente".to_string(), "tel".to_string()]);
+        call.time_entries.push(TimeEntry { start: local(3, 23, 30), end: Some(local(4, 0, 45)) });
+        let mut report = Task::new(2, "Informe".to_string(), None);
+        report.time_entries.push(TimeEntry { start: local(4, 10, 0), end: Some(local(4, 11, 0)) });
+        // En marcha: cuenta hasta `now`
+        report.time_entries.push(TimeEntry { start: local(5, 9, 0), end: None });
+        vec![call, report]
+    }
+
+    #[test]
+    fn test_groups_by_day_splitting_at_midnight() {
+        let sheet = build(&tasks(), None, None, TimesheetGroup::Day, local(5, 9, 30));
+        let rows: Vec<(String, u64, i64)> = sheet.rows.iter().map(|r| (r.group.label(), r.task_id, r.duration.num_minutes())).collect();
+        assert_eq!(rows, vec![
+            ("03/06/2025".to_string(), 1, 30),
+            ("04/06/2025".to_string(), 1, 45),
+            ("04/06/2025".to_string(), 2, 60),
+            ("05/06/2025".to_string(), 2, 30),
+        ]);
+        assert_eq!(format_duration(sheet.total), "2h 45m");
+
+        let day = NaiveDate::from_ymd_opt(2025, 6, 4);
+        let sheet = build(&tasks(), day, day, TimesheetGroup::Day, local(5, 9, 30));
+        assert_eq!(sheet.total.num_minutes(), 105);
+    }
+
+    #[test]
+    fn test_groups_by_tag_and_exports_csv() {
+        let sheet = build(&tasks(), None, None, TimesheetGroup::Tag, local(5, 9, 30));
+        let groups: Vec<String> = sheet.rows.iter().map(|r| r.group.label()).collect();
+        assert_eq!(groups, vec!["cliente", "tel", "Sin tag"]);
+        // El total no cuenta dos veces la tarea con dos tags
+        assert_eq!(sheet.total.num_minutes(), 165);
+
+        let mut output = Vec::new();
+        write_csv(&sheet, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(),
+                   "group,id,title,minutes,hours\ncliente,1,Llamar a Ana,75,1.25\ntel,1,Llamar a Ana,75,1.25\n,2,Informe,90,1.50\n");
+    }
+}
@@ -0,0 +1,445 @@
+use std::io;
+use std::time::Duration;
+use chrono::{Local, Utc};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use crate::cli;
+use crate::task::Task;
+use crate::timesheet;
+use crate::{complete_task, reload_if_changed, save_tasks, snooze_task, try_modify_task, App};
+
+// Interfaz a pantalla completa ('tui'). Usa el mismo storage y las mismas
+// funciones que los comandos; los avisos del scheduler llegan como banners.
+
+const HELP: &str = "↑↓ mover · c completar · t tags · s programar · z posponer · e editar · / buscar · f filtro · x avisos · q salir";
+// Banners visibles a la vez (los más recientes)
+const MAX_BANNERS: usize = 3;
+const DATE_FORMAT: &str = "%d/%m/%Y %H:%M";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Filter {
+    Pending,
+    Completed,
+    All,
+}
+
+impl Filter {
+    fn next(self) -> Self {
+        match self {
+            Filter::Pending => Filter::Completed,
+            Filter::Completed => Filter::All,
+            Filter::All => Filter::Pending,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Filter::Pending => "pendientes",
+            Filter::Completed => "completadas",
+            Filter::All => "todas",
+        }
+    }
+
+    fn matches(self, task: &Task) -> bool {
+        match self {
+            Filter::Pending => !task.is_completed(),
+            Filter::Completed => task.is_completed(),
+            Filter::All => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Prompt {
+    Search,
+    Tags,
+    Schedule,
+    Snooze,
+    Title,
+}
+
+impl Prompt {
+    fn label(self) -> &'static str {
+        match self {
+            Prompt::Search => "🔍 Buscar",
+            Prompt::Tags => "🏷️ Tags (separados por comas)",
+            Prompt::Schedule => "⏰ Programar para",
+            Prompt::Snooze => "⏸️ Posponer (ej: 30, +2h, tonight)",
+            Prompt::Title => "✏️ Título",
+        }
+    }
+}
+
+struct Input {
+    prompt: Prompt,
+    text: String,
+}
+
+struct Tui {
+    filter: Filter,
+    search: String,
+    // Tareas visibles, copiadas del storage en cada vuelta
+    tasks: Vec<Task>,
+    list: ListState,
+    input: Option<Input>,
+    // Resultado de la última acción (reemplaza a la ayuda hasta la próxima tecla)
+    status: Option<String>,
+    banners: Vec<String>,
+    // Los hooks pueden escribir en la terminal: después de una acción se redibuja todo
+    redraw: bool,
+}
+
+pub fn run(app: &App) {
+    app.banners.capture();
+    let mut terminal = ratatui::init();
+    let result = Tui::new().event_loop(&mut terminal, app);
+    ratatui::restore();
+    app.banners.release();
+
+    if let Err(e) = result {
+        eprintln!("❌ Error en la interfaz: {}", e);
+    }
+}
+
+// Tareas que pasan el filtro y la búsqueda (en el título o los tags)
+fn visible(tasks: &[Task], filter: Filter, search: &str) -> Vec<Task> {
+    let search = search.trim().to_lowercase();
+    tasks.iter()
+        .filter(|t| filter.matches(t))
+        .filter(|t| search.is_empty()
+            || t.title.to_lowercase().contains(&search)
+            || t.tags.iter().any(|tag| tag.to_lowercase().contains(&search)))
+        .cloned()
+        .collect()
+}
+
+impl Tui {
+    fn new() -> Self {
+        Self {
+            filter: Filter::Pending,
+            search: String::new(),
+            tasks: Vec::new(),
+            list: ListState::default(),
+            input: None,
+            status: None,
+            banners: Vec::new(),
+            redraw: false,
+        }
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal, app: &App) -> io::Result<()> {
+        loop {
+            self.refresh(app);
+            if self.redraw {
+                terminal.clear()?;
+                self.redraw = false;
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Sin teclas se vuelve a leer el storage para mostrar los cambios del scheduler
+            if !event::poll(Duration::from_millis(500))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match self.input.take() {
+                Some(input) => self.handle_input(app, input, key),
+                None => {
+                    if !self.handle_key(app, key) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    fn refresh(&mut self, app: &App) {
+        let selected_id = self.selected().map(|t| t.id);
+        self.tasks = visible(app.storage.lock().unwrap().get_all_tasks(), self.filter, &self.search);
+
+        // Mantener la misma tarea seleccionada aunque cambie su posición
+        let index = selected_id
+            .and_then(|id| self.tasks.iter().position(|t| t.id == id))
+            .or_else(|| self.list.selected().map(|i| i.min(self.tasks.len().saturating_sub(1))))
+            .unwrap_or(0);
+        self.list.select(if self.tasks.is_empty() { None } else { Some(index) });
+
+        for banner in app.banners.take() {
+            self.banners.push(format!("{} {}", Local::now().format("%H:%M"), banner));
+        }
+        if self.banners.len() > MAX_BANNERS {
+            self.banners.drain(..self.banners.len() - MAX_BANNERS);
+        }
+    }
+
+    fn selected(&self) -> Option<&Task> {
+        self.list.selected().and_then(|i| self.tasks.get(i))
+    }
+
+    // false para salir
+    fn handle_key(&mut self, app: &App, key: KeyEvent) -> bool {
+        self.status = None;
+        let selected = self.selected().cloned();
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.list.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.list.select_previous(),
+            KeyCode::PageDown => self.list.scroll_down_by(10),
+            KeyCode::PageUp => self.list.scroll_up_by(10),
+            KeyCode::Home | KeyCode::Char('g') => self.list.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.list.select_last(),
+            KeyCode::Char('f') => self.filter = self.filter.next(),
+            KeyCode::Char('x') => self.banners.clear(),
+            KeyCode::Char('/') => self.prompt(Prompt::Search, self.search.clone()),
+            _ => {},
+        }
+
+        let Some(task) = selected else { return true };
+        match key.code {
+            KeyCode::Char('c') | KeyCode::Char(' ') if !task.is_completed() => {
+                self.apply(app, |app| {
+                    complete_task(app, task.id).map(|()| format!("✅ Tarea {} marcada como completada", task.id))
+                });
+            },
+            KeyCode::Char('t') => self.prompt(Prompt::Tags, task.tags.join(", ")),
+            KeyCode::Char('s') => self.prompt(Prompt::Schedule, task.scheduled_for.map(|s| s.format(DATE_FORMAT).to_string()).unwrap_or_default()),
+            KeyCode::Char('z') if !task.is_completed() => self.prompt(Prompt::Snooze, String::new()),
+            KeyCode::Char('e') => self.prompt(Prompt::Title, task.title.clone()),
+            _ => {},
+        }
+        true
+    }
+
+    fn prompt(&mut self, prompt: Prompt, text: String) {
+        self.input = Some(Input { prompt, text });
+    }
+
+    fn handle_input(&mut self, app: &App, mut input: Input, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                if input.prompt == Prompt::Search {
+                    self.search.clear();
+                }
+                return;
+            },
+            KeyCode::Enter => {
+                self.submit(app, input);
+                return;
+            },
+            KeyCode::Backspace => {
+                input.text.pop();
+            },
+            KeyCode::Char(c) => input.text.push(c),
+            _ => {},
+        }
+
+        // La búsqueda filtra mientras se escribe
+        if input.prompt == Prompt::Search {
+            self.search = input.text.clone();
+        }
+        self.input = Some(input);
+    }
+
+    fn submit(&mut self, app: &App, input: Input) {
+        let text = input.text.trim().to_string();
+        if input.prompt == Prompt::Search {
+            self.search = text;
+            return;
+        }
+        let Some(id) = self.selected().map(|t| t.id) else { return };
+
+        match input.prompt {
+            Prompt::Search => {},
+            Prompt::Tags => self.apply(app, |app| {
+                try_modify_task(app, id, |task| {
+                    task.clear_tags();
+                    text.split(',').map(str::trim).filter(|t| !t.is_empty()).for_each(|t| task.add_tag(t.to_string()));
+                }).map(|()| format!("🏷️ Tags de la tarea {} actualizados", id))
+            }),
+            Prompt::Schedule => self.apply(app, |app| {
+                let datetime = cli::parse_datetime(&text).map_err(|e| format!("❌ {}", e))?;
+                try_modify_task(app, id, |task| task.schedule_for(datetime))
+                    .map(|()| format!("⏰ Tarea {} programada para {}", id, datetime.format(DATE_FORMAT)))
+            }),
+            Prompt::Snooze => self.apply(app, |app| {
+                snooze_task(app, id, &text).map(|messages| messages.join(" · "))
+            }),
+            Prompt::Title if text.is_empty() => self.status = Some("❌ El título no puede estar vacío".to_string()),
+            Prompt::Title => self.apply(app, |app| {
+                try_modify_task(app, id, |task| task.title = text)
+                    .map(|()| format!("✏️ Título de la tarea {} actualizado", id))
+            }),
+        }
+    }
+
+    // Ejecuta una acción y guarda, como después de un comando. La acción bloquea
+    // las tareas solo lo necesario (los hooks corren sin bloquearlas).
+    fn apply<F>(&mut self, app: &App, action: F)
+    where
+        F: FnOnce(&App) -> Result<String, String>,
+    {
+        // Si otro programa cambió el archivo, aplicar sobre sus cambios y no pisarlos
+        reload_if_changed(app);
+        let result = action(app);
+        save_tasks(app);
+
+        self.status = Some(result.unwrap_or_else(|e| e));
+        self.redraw = true;
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [banner_area, main_area, bottom_area] = Layout::vertical([
+            Constraint::Length(self.banners.len() as u16),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ]).areas(frame.area());
+        let [list_area, detail_area] = Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(main_area);
+
+        let banners: Vec<Line> = self.banners.iter().map(|b| Line::from(b.as_str()).bold().fg(Color::Yellow)).collect();
+        frame.render_widget(Paragraph::new(banners), banner_area);
+
+        let items: Vec<ListItem> = self.tasks.iter().map(|task| {
+            let mut spans = vec![
+                Span::raw(if task.is_completed() { "✅ " } else { "⏳ " }),
+                Span::raw(format!("[{}] ", task.id)).dim(),
+                Span::raw(task.title.clone()),
+            ];
+            if let Some(scheduled) = task.scheduled_for.filter(|_| !task.is_completed()) {
+                spans.push(Span::raw(format!("  🕐 {}", scheduled.format("%d/%m %H:%M"))).fg(Color::Green));
+            }
+            if !task.tags.is_empty() {
+                spans.push(Span::raw(format!("  #{}", task.tags.join(" #"))).fg(Color::Cyan));
+            }
+            ListItem::new(Line::from(spans))
+        }).collect();
+
+        let mut title = format!(" Tareas {} ({}) ", self.filter.name(), self.tasks.len());
+        if !self.search.is_empty() {
+            title.push_str(&format!("· 🔍 {} ", self.search));
+        }
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().reversed())
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let detail = match self.selected() {
+            Some(task) => detail_lines(task),
+            None => vec![Line::from("No hay tareas").dim()],
+        };
+        frame.render_widget(Paragraph::new(detail).wrap(Wrap { trim: false }).block(Block::bordered().title(" Detalle ")), detail_area);
+
+        match &self.input {
+            Some(input) => {
+                let line = format!("{}: {}", input.prompt.label(), input.text);
+                let cursor = bottom_area.x + Line::from(line.as_str()).width() as u16;
+                frame.render_widget(Paragraph::new(line), bottom_area);
+                frame.set_cursor_position((cursor.min(bottom_area.right().saturating_sub(1)), bottom_area.y));
+            },
+            None => {
+                let line = match &self.status {
+                    Some(status) => Line::from(status.as_str()).bold(),
+                    None => Line::from(HELP).dim(),
+                };
+                frame.render_widget(Paragraph::new(line), bottom_area);
+            },
+        }
+    }
+}
+
+fn detail_lines(task: &Task) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(task.title.clone()).bold(), Line::from("")];
+    let mut field = |label: &str, value: String| lines.push(Line::from(vec![Span::raw(format!("{}: ", label)).dim(), Span::raw(value)]));
+
+    field("Estado", if task.is_completed() { "✅ Completada" } else { "⏳ Pendiente" }.to_string());
+    if let Some(ref description) = task.description {
+        field("Descripción", description.clone());
+    }
+    if !task.tags.is_empty() {
+        field("Tags", task.tags.join(", "));
+    }
+    if let Some(priority) = task.priority {
+        field("Prioridad", priority.name().to_string());
+    }
+    if let Some(ref project) = task.project {
+        field("Proyecto", project.clone());
+    }
+    if let Some(scheduled) = task.scheduled_for {
+        field("Programada", scheduled.format(DATE_FORMAT).to_string());
+    }
+    if let Some(snoozed) = task.snoozed_until {
+        field("Pospuesta hasta", format!("{} ({} veces)", snoozed.format(DATE_FORMAT), task.snooze_count));
+    }
+    if let Some(due) = task.due {
+        field("Vence", due.format(DATE_FORMAT).to_string());
+    }
+    field("Creada", task.created_at.with_timezone(&Local).format(DATE_FORMAT).to_string());
+    if let Some(completed) = task.completed_at {
+        field("Completada", completed.with_timezone(&Local).format(DATE_FORMAT).to_string());
+    }
+    if !task.time_entries.is_empty() {
+        let running = if task.is_tracking() { " (en marcha)" } else { "" };
+        field("Tiempo registrado", format!("{}{}", timesheet::format_duration(task.tracked_time(Utc::now())), running));
+    }
+    if let Some(estimate) = task.estimate() {
+        field("Estimación", timesheet::format_duration(estimate));
+    }
+    if !task.pomodoros.is_empty() {
+        field("Pomodoros", task.pomodoros.len().to_string());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn tasks() -> Vec<Task> {
+        let mut tasks = vec![
+            Task::new_with_tags(1, "Comprar pan".to_string(), None, vec!["casa".to_string()]),
+            Task::new(2, "Llamar a Ana".to_string(), None),
+            Task::new_with_tags(3, "Pagar luz".to_string(), None, vec!["casa".to_string()]),
+        ];
+        tasks[2].complete();
+        tasks
+    }
+
+    #[test]
+    fn test_visible_filters_by_status_and_search() {
+        let ids = |tasks: Vec<Task>| tasks.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(visible(&tasks(), Filter::Pending, "")), vec![1, 2]);
+        assert_eq!(ids(visible(&tasks(), Filter::All, "CASA")), vec![1, 3]);
+        assert_eq!(ids(visible(&tasks(), Filter::Completed, "ana")), Vec::<u64>::new());
+        assert_eq!(Filter::All.next(), Filter::Pending);
+    }
+
+    #[test]
+    fn test_draws_list_detail_and_banners() {
+        let mut tui = Tui::new();
+        tui.tasks = visible(&tasks(), Filter::Pending, "");
+        tui.list.select(Some(1));
+        tui.banners.push("09:00 Recordatorio: [2] Llamar a Ana".to_string());
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        terminal.draw(|frame| tui.draw(frame)).unwrap();
+        let screen = format!("{:?}", terminal.backend().buffer());
+
+        assert!(screen.contains("09:00 Recordatorio"));
+        assert!(screen.contains("Tareas pendientes (2)"));
+        assert!(screen.contains("Comprar pan"));
+        // El detalle muestra la tarea seleccionada
+        assert!(screen.contains("Detalle"));
+        assert!(screen.matches("Llamar a Ana").count() >= 3);
+        assert!(screen.contains("c completar"));
+    }
+}
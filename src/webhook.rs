@@ -0,0 +1,331 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::config::{Config, WebhookConfig};
+use crate::notifier::{Notification, Notifier};
+use crate::task::Task;
+use crate::template;
+
+// Reintentos: 30s, 1m, 2m, 4m... hasta 1h entre intentos
+const MAX_ATTEMPTS: u32 = 10;
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 3600;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Un envío pendiente a un webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delivery {
+    pub id: u64,
+    pub url: String,
+    pub payload: String,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    // Envío en curso en otro hilo (no se persiste)
+    #[serde(skip)]
+    in_flight: bool,
+}
+
+// Cola persistente de envíos: sobrevive a reinicios para reintentar lo que falló
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Outbox {
+    deliveries: Vec<Delivery>,
+    next_id: u64,
+    #[serde(skip)]
+    path: Option<String>,
+}
+
+impl Outbox {
+    pub fn load_from_file(path: &str) -> Result<Self, io::Error> {
+        let mut outbox = if Path::new(path).exists() {
+            let data = fs::read_to_string(path)?;
+            serde_json::from_str(&data).map_err(io::Error::other)?
+        } else {
+            Outbox::default()
+        };
+
+        outbox.path = Some(path.to_string());
+        Ok(outbox)
+    }
+
+    pub fn get_deliveries(&self) -> &Vec<Delivery> {
+        &self.deliveries
+    }
+
+    fn save(&self) {
+        if let Some(ref path) = self.path {
+            let result = serde_json::to_string_pretty(self)
+                .map_err(io::Error::other)
+                .and_then(|json| fs::write(path, json));
+            if let Err(e) = result {
+                eprintln!("⚠️ Error al guardar la cola de webhooks: {}", e);
+            }
+        }
+    }
+
+    fn enqueue(&mut self, url: String, payload: String) {
+        self.next_id += 1;
+        self.deliveries.push(Delivery {
+            id: self.next_id,
+            url,
+            payload,
+            attempts: 0,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+            in_flight: false,
+        });
+        self.save();
+    }
+
+    // Marca como en curso los envíos que ya deben intentarse y los retorna
+    fn take_due(&mut self, now: DateTime<Utc>) -> Vec<Delivery> {
+        let mut due = Vec::new();
+        for delivery in self.deliveries.iter_mut() {
+            if !delivery.in_flight && delivery.next_attempt_at <= now {
+                delivery.in_flight = true;
+                due.push(delivery.clone());
+            }
+        }
+        due
+    }
+
+    fn record_result(&mut self, id: u64, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.deliveries.retain(|d| d.id != id),
+            Err(e) => {
+                if let Some(delivery) = self.deliveries.iter_mut().find(|d| d.id == id) {
+                    delivery.in_flight = false;
+                    delivery.attempts += 1;
+                    delivery.next_attempt_at = Utc::now() + backoff(delivery.attempts);
+                    delivery.last_error = Some(e);
+
+                    if delivery.attempts >= MAX_ATTEMPTS {
+                        eprintln!("⚠️ Webhook {} descartado tras {} intentos: {}",
+                                  delivery.url, delivery.attempts, delivery.last_error.as_deref().unwrap_or(""));
+                    }
+                }
+                self.deliveries.retain(|d| d.attempts < MAX_ATTEMPTS);
+            }
+        }
+        self.save();
+    }
+
+    // Adelanta todos los reintentos pendientes para que se envíen ya
+    pub fn retry_now(&mut self) {
+        let now = Utc::now();
+        for delivery in self.deliveries.iter_mut() {
+            delivery.next_attempt_at = now;
+        }
+        self.save();
+    }
+}
+
+fn backoff(attempts: u32) -> chrono::Duration {
+    let secs = BASE_BACKOFF_SECS.saturating_mul(1 << attempts.saturating_sub(1).min(16));
+    chrono::Duration::seconds(secs.min(MAX_BACKOFF_SECS))
+}
+
+// POSTea un JSON por cada evento a los webhooks configurados
+pub struct WebhookNotifier {
+    outbox: Arc<Mutex<Outbox>>,
+}
+
+impl WebhookNotifier {
+    pub fn new(outbox: Arc<Mutex<Outbox>>) -> Self {
+        Self { outbox }
+    }
+
+    // Intenta enviar todo lo que esté listo (bloquea mientras dura el envío)
+    pub fn flush(outbox: &Arc<Mutex<Outbox>>) {
+        let due = outbox.lock().unwrap().take_due(Utc::now());
+
+        for delivery in due {
+            let result = post_json(&delivery.url, &delivery.payload);
+            outbox.lock().unwrap().record_result(delivery.id, result);
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, config: &Config, notification: &Notification) {
+        let mut enqueued = false;
+
+        for webhook in config.webhooks.iter().filter(|w| w.events.contains(&notification.event)) {
+            match render_payload(webhook, notification) {
+                Ok(payload) => {
+                    self.outbox.lock().unwrap().enqueue(webhook.url.clone(), payload);
+                    enqueued = true;
+                }
+                Err(e) => eprintln!("⚠️ Plantilla inválida para el webhook {}: {}", webhook.url, e),
+            }
+        }
+
+        // Enviar en segundo plano para no bloquear la terminal
+        if enqueued {
+            let outbox = Arc::clone(&self.outbox);
+            thread::spawn(move || Self::flush(&outbox));
+        }
+    }
+
+    fn tick(&self, _config: &Config) {
+        Self::flush(&self.outbox);
+    }
+}
+
+// Sin plantilla: {"event": "...", "task": {...}}.
+// Con plantilla: los marcadores {{title}}, {{id}}... se reemplazan por valores
+// escapados para JSON, y {{task}} por la tarea completa en JSON.
+pub fn render_payload(webhook: &WebhookConfig, notification: &Notification) -> Result<String, String> {
+    let task_json = serde_json::to_value(notification.task).map_err(|e| e.to_string())?;
+
+    let template = match webhook.template {
+        Some(ref template) => template,
+        None => {
+            let payload = serde_json::json!({
+                "event": notification.event.name(),
+                "task": task_json,
+            });
+            return Ok(payload.to_string());
+        }
+    };
+
+    let task = notification.task;
+    let payload = template::render(template, |key| {
+        if key == "task" {
+            return Some(task_json.to_string());
+        }
+        field_value(key, notification.event.name(), task).map(|value| escape_json(&value))
+    });
+
+    // La plantilla debe producir JSON válido
+    serde_json::from_str::<serde_json::Value>(&payload).map_err(|e| e.to_string())?;
+    Ok(payload)
+}
+
+fn field_value(key: &str, event: &str, task: &Task) -> Option<String> {
+    let value = match key {
+        "event" => event.to_string(),
+        "id" => task.id.to_string(),
+        "title" => task.title.clone(),
+        "description" => task.description.clone().unwrap_or_default(),
+        "tags" => task.tags.join(", "),
+        "status" => if task.is_completed() { "completed".to_string() } else { "pending".to_string() },
+        "scheduled_for" => task.scheduled_for.map(|d| d.to_rfc3339()).unwrap_or_default(),
+        "snooze_count" => task.snooze_count.to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+// Escapa un texto para insertarlo dentro de un string JSON (sin las comillas)
+fn escape_json(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+fn post_json(url: &str, payload: &str) -> Result<(), String> {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+
+    match agent.post(url).set("Content-Type", "application/json").send_string(payload) {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, _)) => Err(format!("HTTP {}", code)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use crate::notifier::TaskEvent;
+
+    // Servidor HTTP mínimo: responde `status` a una petición y retorna su cuerpo
+    fn stub_server(status: u16) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+
+            // Leer cabeceras y cuerpo según Content-Length
+            loop {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let length = text.lines()
+                        .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + length {
+                        break;
+                    }
+                }
+            }
+
+            let response = format!("HTTP/1.1 {} Stub\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+            stream.write_all(response.as_bytes()).unwrap();
+
+            let text = String::from_utf8_lossy(&request).to_string();
+            text.split("\r\n\r\n").nth(1).unwrap_or("").to_string()
+        });
+
+        (url, handle)
+    }
+
+    fn outbox() -> Arc<Mutex<Outbox>> {
+        Arc::new(Mutex::new(Outbox::default()))
+    }
+
+    #[test]
+    fn test_render_payload_with_template() {
+        let task = Task::new(7, "Llamar a \"Ana\"".to_string(), None);
+        let notification = Notification { event: TaskEvent::Reminder, task: &task, escalated: false };
+        let webhook = WebhookConfig {
+            url: "http://localhost".to_string(),
+            events: WebhookConfig::default_events(),
+            template: Some(r#"{"text": "[{{event}}] {{title}}", "id": {{id}}}"#.to_string()),
+        };
+
+        let payload = render_payload(&webhook, &notification).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(value["text"], "[reminder] Llamar a \"Ana\"");
+        assert_eq!(value["id"], 7);
+    }
+
+    #[test]
+    fn test_delivery_posts_payload() {
+        let (url, server) = stub_server(200);
+        let outbox = outbox();
+        outbox.lock().unwrap().enqueue(url, r#"{"hola": 1}"#.to_string());
+
+        WebhookNotifier::flush(&outbox);
+
+        assert_eq!(server.join().unwrap(), r#"{"hola": 1}"#);
+        assert!(outbox.lock().unwrap().get_deliveries().is_empty());
+    }
+
+    #[test]
+    fn test_failed_delivery_is_kept_with_backoff() {
+        let (url, server) = stub_server(500);
+        let outbox = outbox();
+        outbox.lock().unwrap().enqueue(url, "{}".to_string());
+
+        WebhookNotifier::flush(&outbox);
+        server.join().unwrap();
+
+        let outbox = outbox.lock().unwrap();
+        let delivery = &outbox.get_deliveries()[0];
+        assert_eq!(delivery.attempts, 1);
+        assert_eq!(delivery.last_error.as_deref(), Some("HTTP 500"));
+        assert!(delivery.next_attempt_at > Utc::now());
+    }
+}